@@ -17,6 +17,7 @@ pub struct Display {
     events_loop: RefCell<EventsLoop>,
     width: Cell<u32>,
     height: Cell<u32>,
+    resized: Cell<bool>,
 }
 
 impl Display {
@@ -68,6 +69,7 @@ impl Display {
             proj_matrix: Cell::new(proj_matrix),
             width: Cell::new(width),
             height: Cell::new(height),
+            resized: Cell::new(false),
         }
     }
 
@@ -103,6 +105,7 @@ impl Display {
                 _ => (),
             });
 
+        self.resized.set(dimensions.is_some());
         if let Some((width, height)) = dimensions {
             self.width.set(width);
             self.height.set(height);
@@ -133,6 +136,16 @@ impl Display {
         self.update_view()
     }
 
+    /// Returns the inner dimensions of the window in pixels
+    pub(crate) fn get_dimensions(&self) -> (u32, u32) {
+        (self.width.get(), self.height.get())
+    }
+
+    /// Returns whether the window was resized during the last refresh
+    pub(crate) fn was_resized(&self) -> bool {
+        self.resized.get()
+    }
+
     #[cfg(test)]
     pub(crate) fn update_virtual_keycode(&mut self, keycode: VirtualKeyCode, pressed: bool) {
         self.input
@@ -0,0 +1,105 @@
+//! Packing of glyphs into the atlas of a Font at runtime.
+
+use super::sdf::generate_sdf;
+use super::Font;
+
+/// Padding (in pixels) between glyphs packed at runtime, so linear filtering doesn't bleed neighbouring glyphs together.
+pub(crate) const GLYPH_PADDING: u32 = 1;
+
+/// Keeps track of where the next glyph can be placed in the atlas, placing glyphs in rows ("shelves") from left to right.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct AtlasPacker {
+    cursor_x: u32,
+    cursor_y: u32,
+    shelf_height: u32,
+}
+
+impl AtlasPacker {
+    /// Creates a new packer, that starts packing glyphs at the given y position in the atlas.
+    pub(crate) fn new(y: u32) -> AtlasPacker {
+        AtlasPacker {
+            cursor_x: 0,
+            cursor_y: y,
+            shelf_height: 0,
+        }
+    }
+
+    /// Moves the packer to the start of a new shelf at the given y position in the atlas.
+    pub(crate) fn move_to_new_shelf(&mut self, y: u32) {
+        self.cursor_x = 0;
+        self.cursor_y = y;
+        self.shelf_height = 0;
+    }
+}
+
+impl Font {
+    /// Finds room for a glyph of the given dimensions in the atlas, growing the atlas if necessary.
+    /// Returns the position of the top-left corner of the region.
+    pub(crate) fn allocate_atlas_region(&mut self, width: u32, height: u32) -> (u32, u32) {
+        let padded_width = width + GLYPH_PADDING;
+        let padded_height = height + GLYPH_PADDING;
+
+        // Glyphs packed into a font that wasn't built by packing start after the existing atlas
+        let current_height = self.height;
+        let mut packer = self
+            .packer
+            .take()
+            .unwrap_or_else(|| AtlasPacker::new(current_height));
+
+        if padded_width > self.width {
+            let height = self.height;
+            self.resize_atlas(padded_width, height);
+        }
+        if packer.cursor_x + padded_width > self.width {
+            let y = packer.cursor_y + packer.shelf_height;
+            packer.move_to_new_shelf(y);
+        }
+        let mut new_height = self.height.max(1);
+        while packer.cursor_y + padded_height > new_height {
+            new_height *= 2;
+        }
+        if new_height != self.height {
+            let width = self.width;
+            self.resize_atlas(width, new_height);
+        }
+
+        let position = (packer.cursor_x, packer.cursor_y);
+        packer.cursor_x += padded_width;
+        packer.shelf_height = packer.shelf_height.max(padded_height);
+        self.packer = Some(packer);
+        self.atlas_version = self.atlas_version.wrapping_add(1);
+
+        position
+    }
+
+    /// Packs a glyph into the atlas from its alpha values (`width * height` bytes, row by row) as white pixels.
+    /// If the font is an SDF font, the glyph is converted into a signed distance field first, which pads it by the spread.
+    /// Returns the region (x, y, width, height) the glyph was placed in.
+    pub(crate) fn pack_alpha_glyph(
+        &mut self,
+        alpha: &[u8],
+        width: u32,
+        height: u32,
+    ) -> (u32, u32, u32, u32) {
+        let (alpha, width, height) = match self.sdf_spread {
+            Some(spread) => (
+                generate_sdf(alpha, width, height, spread),
+                width + spread * 2,
+                height + spread * 2,
+            ),
+            None => (alpha.to_vec(), width, height),
+        };
+
+        let (atlas_x, atlas_y) = self.allocate_atlas_region(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                let index = (((atlas_y + y) * self.width + atlas_x + x) * 4) as usize;
+                self.image_buffer[index] = 255;
+                self.image_buffer[index + 1] = 255;
+                self.image_buffer[index + 2] = 255;
+                self.image_buffer[index + 3] = alpha[(y * width + x) as usize];
+            }
+        }
+        (atlas_x, atlas_y, width, height)
+    }
+}
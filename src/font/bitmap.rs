@@ -0,0 +1,523 @@
+//! Loading of the Linux console (PSF1 and PSF2) and X11 (BDF) bitmap font formats.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::Read;
+use std::path::PathBuf;
+use std::str;
+
+use super::grid::CP437;
+use super::reload::{FontOrigin, FontSource};
+use super::{CharacterData, Font, FontError};
+
+const PSF1_MAGIC: [u8; 2] = [0x36, 0x04];
+const PSF1_MODE_512: u8 = 0x01;
+const PSF1_MODE_HAS_TABLE: u8 = 0x02;
+const PSF1_MODE_HAS_SEQUENCES: u8 = 0x04;
+const PSF1_SEPARATOR: u16 = 0xFFFF;
+const PSF1_START_SEQUENCE: u16 = 0xFFFE;
+
+const PSF2_MAGIC: [u8; 4] = [0x72, 0xb5, 0x4a, 0x86];
+const PSF2_HAS_UNICODE_TABLE: u32 = 0x01;
+const PSF2_SEPARATOR: u8 = 0xFF;
+const PSF2_START_SEQUENCE: u8 = 0xFE;
+
+/// Glyphs per row in the generated atlas
+const ATLAS_COLUMNS: u32 = 16;
+/// The largest width or height of a glyph; larger ones are rejected as invalid
+const MAX_GLYPH_SIZE: u32 = 256;
+/// The largest width or height of the generated atlas, which is as large as most GPUs can take as a texture
+const MAX_ATLAS_SIZE: u32 = 16384;
+
+/// A single monochrome glyph, before it is packed into the atlas.
+struct BitmapGlyph {
+    characters: Vec<char>,
+    width: u32,
+    height: u32,
+    x_off: i32,
+    y_off: i32,
+    /// Row-major, `width * height` pixels, true where the glyph is drawn
+    pixels: Vec<bool>,
+}
+
+impl Font {
+    /// Loads a PSF1 or PSF2 font (the format of Linux console fonts, ie. in `/usr/share/consolefonts`). Example:
+    ///
+    /// ```no_run
+    /// use glerminal::font::Font;
+    /// let font = Font::from_psf("/usr/share/consolefonts/Lat2-Terminus16.psf").unwrap();
+    /// ```
+    ///
+    /// If the font has no unicode table, its first 256 glyphs are mapped in code page 437 order.
+    /// Compressed (`.psf.gz`) fonts must be decompressed first.
+    pub fn from_psf<T: Into<PathBuf>>(psf_path: T) -> Result<Font, FontError> {
+        let psf_path = psf_path.into();
+        let mut psf_data = Vec::new();
+        File::open(&psf_path)?.read_to_end(&mut psf_data)?;
+        let mut font = Font::from_psf_raw(&psf_data)?;
+        if let Some(stem) = psf_path.file_stem() {
+            font.name = stem.to_string_lossy().into_owned();
+        }
+        font.origin = FontOrigin(Some(FontSource::Psf(psf_path)));
+        Ok(font)
+    }
+
+    /// Loads a PSF1 or PSF2 font from the given bytes (ie. from `include_bytes!`), see [`from_psf`](#method.from_psf).
+    pub fn from_psf_raw(psf_data: &[u8]) -> Result<Font, FontError> {
+        let (glyphs, width, height) = if psf_data.starts_with(&PSF1_MAGIC) {
+            parse_psf1(psf_data)?
+        } else if psf_data.starts_with(&PSF2_MAGIC) {
+            parse_psf2(psf_data)?
+        } else {
+            return Err(FontError::Psf("Not a PSF font".to_owned()));
+        };
+        build_bitmap_font("PSF".to_owned(), width, height, glyphs).map_err(FontError::Psf)
+    }
+
+    /// Loads a BDF font (the X11 bitmap font format). Example:
+    ///
+    /// ```no_run
+    /// use glerminal::font::Font;
+    /// let font = Font::from_bdf("fonts/terminus.bdf").unwrap();
+    /// ```
+    pub fn from_bdf<T: Into<PathBuf>>(bdf_path: T) -> Result<Font, FontError> {
+        let bdf_path = bdf_path.into();
+        let mut bdf_content = String::new();
+        File::open(&bdf_path)?.read_to_string(&mut bdf_content)?;
+        let mut font = Font::from_bdf_raw(bdf_content)?;
+        font.origin = FontOrigin(Some(FontSource::Bdf(bdf_path)));
+        Ok(font)
+    }
+
+    /// Loads a BDF font from the given string (.bdf file contents), see [`from_bdf`](#method.from_bdf).
+    pub fn from_bdf_raw<T: Into<String>>(bdf_content: T) -> Result<Font, FontError> {
+        let bdf_content = bdf_content.into();
+        let mut name = "BDF".to_owned();
+        let mut bounding_box: Option<(u32, u32, i32, i32)> = None;
+        let mut ascent: Option<i32> = None;
+        let mut descent: Option<i32> = None;
+        let mut glyphs = Vec::new();
+
+        let mut lines = bdf_content.lines();
+        while let Some(line) = lines.next() {
+            let mut parts = line.split_whitespace();
+            match parts.next() {
+                Some("FONT") => name = line["FONT".len()..].trim().to_owned(),
+                Some("FONTBOUNDINGBOX") => {
+                    let values = parse_bdf_numbers(parts, 4, line)?;
+                    let (width, height) = bdf_dimensions(&values, line)?;
+                    bounding_box = Some((width, height, values[2], values[3]));
+                }
+                Some("FONT_ASCENT") => ascent = Some(parse_bdf_numbers(parts, 1, line)?[0]),
+                Some("FONT_DESCENT") => descent = Some(parse_bdf_numbers(parts, 1, line)?[0]),
+                Some("STARTCHAR") => {
+                    let (_, font_height, font_x_off, font_y_off) = match bounding_box {
+                        Some(bounding_box) => bounding_box,
+                        None => {
+                            return Err(FontError::Bdf(
+                                "STARTCHAR before FONTBOUNDINGBOX".to_owned(),
+                            ))
+                        }
+                    };
+                    let default_ascent = font_y_off.checked_add(font_height as i32);
+                    let ascent = match ascent.or(default_ascent) {
+                        Some(ascent) => ascent,
+                        None => {
+                            return Err(FontError::Bdf(
+                                "FONTBOUNDINGBOX offset is out of range".to_owned(),
+                            ))
+                        }
+                    };
+                    if let Some(glyph) = parse_bdf_char(&mut lines, ascent, font_x_off)? {
+                        glyphs.push(glyph);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        let (font_width, font_height) = match bounding_box {
+            Some((width, height, _, _)) => (width, height),
+            None => return Err(FontError::Bdf("FONTBOUNDINGBOX missing".to_owned())),
+        };
+        let line_height = match (ascent, descent) {
+            (Some(ascent), Some(descent)) => ascent.checked_add(descent).unwrap_or(-1),
+            _ => font_height as i32,
+        };
+        if line_height <= 0 || line_height as u32 > MAX_GLYPH_SIZE {
+            return Err(FontError::Bdf(format!(
+                "Invalid line height: {}",
+                line_height
+            )));
+        }
+
+        build_bitmap_font(name, font_width, line_height as u32, glyphs).map_err(FontError::Bdf)
+    }
+}
+
+fn parse_psf1(data: &[u8]) -> Result<(Vec<BitmapGlyph>, u32, u32), FontError> {
+    if data.len() < 4 {
+        return Err(FontError::Psf("PSF1 header is truncated".to_owned()));
+    }
+    let mode = data[2];
+    let height = data[3] as u32;
+    let glyph_count = if mode & PSF1_MODE_512 != 0 { 512 } else { 256 };
+    let glyph_size = height as usize;
+    let glyphs_end = 4 + glyph_count * glyph_size;
+    if data.len() < glyphs_end {
+        return Err(FontError::Psf("PSF1 glyph data is truncated".to_owned()));
+    }
+
+    let mut characters = vec![Vec::new(); glyph_count];
+    if mode & (PSF1_MODE_HAS_TABLE | PSF1_MODE_HAS_SEQUENCES) != 0 {
+        let mut glyph = 0;
+        let mut in_sequence = false;
+        for entry in data[glyphs_end..].chunks(2) {
+            if glyph >= glyph_count || entry.len() < 2 {
+                break;
+            }
+            let value = entry[0] as u16 | (entry[1] as u16) << 8;
+            if value == PSF1_SEPARATOR {
+                glyph += 1;
+                in_sequence = false;
+            } else if value == PSF1_START_SEQUENCE {
+                in_sequence = true;
+            } else if !in_sequence {
+                if let Some(character) = ::std::char::from_u32(value as u32) {
+                    characters[glyph].push(character);
+                }
+            }
+        }
+    } else {
+        set_cp437_characters(&mut characters);
+    }
+
+    let glyphs = characters
+        .into_iter()
+        .enumerate()
+        .map(|(index, characters)| {
+            let start = 4 + index * glyph_size;
+            psf_glyph(characters, 8, height, &data[start..start + glyph_size])
+        })
+        .collect();
+    Ok((glyphs, 8, height))
+}
+
+fn parse_psf2(data: &[u8]) -> Result<(Vec<BitmapGlyph>, u32, u32), FontError> {
+    if data.len() < 32 {
+        return Err(FontError::Psf("PSF2 header is truncated".to_owned()));
+    }
+    let read_u32 = |offset: usize| {
+        data[offset] as u32
+            | (data[offset + 1] as u32) << 8
+            | (data[offset + 2] as u32) << 16
+            | (data[offset + 3] as u32) << 24
+    };
+    let header_size = read_u32(8) as usize;
+    let flags = read_u32(12);
+    let glyph_count = read_u32(16) as usize;
+    let glyph_size = read_u32(20) as usize;
+    let height = read_u32(24);
+    let width = read_u32(28);
+    if width == 0 || height == 0 || glyph_size == 0 {
+        return Err(FontError::Psf("PSF2 glyphs have no pixels".to_owned()));
+    }
+    if width > MAX_GLYPH_SIZE || height > MAX_GLYPH_SIZE {
+        return Err(FontError::Psf("PSF2 glyphs are too large".to_owned()));
+    }
+    if glyph_size < ((width as usize + 7) / 8) * height as usize {
+        return Err(FontError::Psf(
+            "PSF2 glyph size is too small for its dimensions".to_owned(),
+        ));
+    }
+    // Checked before multiplying, so that a huge glyph count can't overflow or allocate before failing
+    if header_size > data.len() || glyph_count > (data.len() - header_size) / glyph_size {
+        return Err(FontError::Psf("PSF2 glyph data is truncated".to_owned()));
+    }
+    let glyphs_end = header_size + glyph_count * glyph_size;
+
+    let mut characters = vec![Vec::new(); glyph_count];
+    if flags & PSF2_HAS_UNICODE_TABLE != 0 {
+        let mut glyph = 0;
+        let mut in_sequence = false;
+        let mut start = glyphs_end;
+        for index in glyphs_end..data.len() {
+            if glyph >= glyph_count {
+                break;
+            }
+            let byte = data[index];
+            if byte == PSF2_SEPARATOR || byte == PSF2_START_SEQUENCE {
+                if !in_sequence {
+                    if let Ok(text) = str::from_utf8(&data[start..index]) {
+                        characters[glyph].extend(text.chars());
+                    }
+                }
+                if byte == PSF2_SEPARATOR {
+                    glyph += 1;
+                    in_sequence = false;
+                } else {
+                    in_sequence = true;
+                }
+                start = index + 1;
+            }
+        }
+    } else {
+        set_cp437_characters(&mut characters);
+    }
+
+    let glyphs = characters
+        .into_iter()
+        .enumerate()
+        .map(|(index, characters)| {
+            let start = header_size + index * glyph_size;
+            psf_glyph(characters, width, height, &data[start..start + glyph_size])
+        })
+        .collect();
+    Ok((glyphs, width, height))
+}
+
+fn set_cp437_characters(characters: &mut Vec<Vec<char>>) {
+    for (index, characters) in characters.iter_mut().enumerate().take(CP437.len()) {
+        characters.push(CP437[index]);
+    }
+}
+
+fn psf_glyph(characters: Vec<char>, width: u32, height: u32, data: &[u8]) -> BitmapGlyph {
+    let row_size = (width as usize + 7) / 8;
+    let mut pixels = Vec::with_capacity(width as usize * height as usize);
+    for y in 0..height as usize {
+        for x in 0..width as usize {
+            let byte = data[y * row_size + x / 8];
+            pixels.push(byte & (0x80 >> (x % 8)) != 0);
+        }
+    }
+    BitmapGlyph {
+        characters,
+        width,
+        height,
+        x_off: 0,
+        y_off: 0,
+        pixels,
+    }
+}
+
+fn parse_bdf_numbers<'a, I: Iterator<Item = &'a str>>(
+    parts: I,
+    amount: usize,
+    line: &str,
+) -> Result<Vec<i32>, FontError> {
+    let numbers = parts
+        .take(amount)
+        .map(|part| part.parse::<i32>())
+        .collect::<Result<Vec<i32>, _>>()
+        .map_err(|_| FontError::Bdf(format!("Invalid numbers in line: '{}'", line)))?;
+    if numbers.len() != amount {
+        return Err(FontError::Bdf(format!(
+            "Missing numbers in line: '{}'",
+            line
+        )));
+    }
+    Ok(numbers)
+}
+
+/// Returns the width and height from the numbers of a bounding box (`FONTBOUNDINGBOX` or `BBX`),
+/// which can't be negative or larger than `MAX_GLYPH_SIZE`.
+fn bdf_dimensions(values: &[i32], line: &str) -> Result<(u32, u32), FontError> {
+    if values[0] < 0 || values[1] < 0 {
+        return Err(FontError::Bdf(format!(
+            "Negative dimensions in line: '{}'",
+            line
+        )));
+    }
+    if values[0] as u32 > MAX_GLYPH_SIZE || values[1] as u32 > MAX_GLYPH_SIZE {
+        return Err(FontError::Bdf(format!(
+            "Too large dimensions in line: '{}'",
+            line
+        )));
+    }
+    Ok((values[0] as u32, values[1] as u32))
+}
+
+/// Parses a single character, starting from the line after `STARTCHAR` and ending at `ENDCHAR`.
+/// Returns `None` if the character has no unicode encoding.
+fn parse_bdf_char<'a, I: Iterator<Item = &'a str>>(
+    lines: &mut I,
+    ascent: i32,
+    font_x_off: i32,
+) -> Result<Option<BitmapGlyph>, FontError> {
+    let mut encoding: Option<u32> = None;
+    let mut bbx: Option<(u32, u32, i32, i32)> = None;
+    let mut pixels = Vec::new();
+
+    while let Some(line) = lines.next() {
+        let mut parts = line.split_whitespace();
+        match parts.next() {
+            Some("ENCODING") => {
+                let value = parse_bdf_numbers(parts, 1, line)?[0];
+                if value >= 0 {
+                    encoding = Some(value as u32);
+                }
+            }
+            Some("BBX") => {
+                let values = parse_bdf_numbers(parts, 4, line)?;
+                let (width, height) = bdf_dimensions(&values, line)?;
+                bbx = Some((width, height, values[2], values[3]));
+            }
+            Some("BITMAP") => {
+                let (width, height, _, _) = match bbx {
+                    Some(bbx) => bbx,
+                    None => return Err(FontError::Bdf("BITMAP before BBX".to_owned())),
+                };
+                for _ in 0..height {
+                    let row = match lines.next() {
+                        Some(row) => row.trim(),
+                        None => return Err(FontError::Bdf("BITMAP is truncated".to_owned())),
+                    };
+                    for x in 0..width as usize {
+                        let digit = row
+                            .get(x / 4..x / 4 + 1)
+                            .and_then(|digit| u8::from_str_radix(digit, 16).ok());
+                        let digit = match digit {
+                            Some(digit) => digit,
+                            None => {
+                                return Err(FontError::Bdf(format!(
+                                    "Invalid BITMAP row: '{}'",
+                                    row
+                                )))
+                            }
+                        };
+                        pixels.push(digit & (0x8 >> (x % 4)) != 0);
+                    }
+                }
+            }
+            Some("ENDCHAR") => break,
+            _ => (),
+        }
+    }
+
+    let character = match encoding.and_then(::std::char::from_u32) {
+        Some(character) => character,
+        None => return Ok(None),
+    };
+    let (width, height, x_off, y_off) = match bbx {
+        Some(bbx) => bbx,
+        None => {
+            return Err(FontError::Bdf(format!(
+                "BBX missing for character {}",
+                character as u32
+            )))
+        }
+    };
+    if pixels.len() != (width * height) as usize {
+        return Err(FontError::Bdf(format!(
+            "BITMAP missing for character {}",
+            character as u32
+        )));
+    }
+    let offsets = x_off.checked_sub(font_x_off).and_then(|x_off| {
+        y_off
+            .checked_add(height as i32)
+            .and_then(|top| ascent.checked_sub(top))
+            .map(|y_off| (x_off, y_off))
+    });
+    let (x_off, y_off) = match offsets {
+        Some(offsets) => offsets,
+        None => {
+            return Err(FontError::Bdf(format!(
+                "BBX offset is out of range for character {}",
+                character as u32
+            )))
+        }
+    };
+
+    Ok(Some(BitmapGlyph {
+        characters: vec![character],
+        width,
+        height,
+        x_off,
+        y_off,
+        pixels,
+    }))
+}
+
+/// Packs the given glyphs into an RGBA atlas and creates a Font out of them.
+/// Returns an error if the atlas would be larger than `MAX_ATLAS_SIZE`.
+fn build_bitmap_font(
+    name: String,
+    size: u32,
+    line_height: u32,
+    glyphs: Vec<BitmapGlyph>,
+) -> Result<Font, String> {
+    let glyphs: Vec<BitmapGlyph> = glyphs
+        .into_iter()
+        .filter(|glyph| !glyph.characters.is_empty())
+        .collect();
+
+    let cell_width = glyphs.iter().map(|glyph| glyph.width).max().unwrap_or(0) + 1;
+    let cell_height = glyphs.iter().map(|glyph| glyph.height).max().unwrap_or(0) + 1;
+    let rows = (glyphs.len() + ATLAS_COLUMNS as usize - 1) / ATLAS_COLUMNS as usize;
+    // The glyphs are at most `MAX_GLYPH_SIZE` in size, but there may be any amount of them
+    let width = cell_width * ATLAS_COLUMNS;
+    let height = match (cell_height as usize).checked_mul(rows.max(1)) {
+        Some(height) if height <= MAX_ATLAS_SIZE as usize => height as u32,
+        _ => return Err("Too many glyphs to fit into the atlas".to_owned()),
+    };
+
+    let mut image_buffer = vec![0; width as usize * height as usize * 4];
+    let mut characters = HashMap::<char, CharacterData>::new();
+    let mut min_off_y = 0;
+
+    let width_float = width as f32;
+    let height_float = height as f32;
+    for (index, glyph) in glyphs.iter().enumerate() {
+        let atlas_x = (index as u32 % ATLAS_COLUMNS) * cell_width;
+        let atlas_y = (index as u32 / ATLAS_COLUMNS) * cell_height;
+        for y in 0..glyph.height {
+            for x in 0..glyph.width {
+                if glyph.pixels[(y * glyph.width + x) as usize] {
+                    let pixel = (((atlas_y + y) * width + atlas_x + x) * 4) as usize;
+                    image_buffer[pixel..pixel + 4].copy_from_slice(&[255, 255, 255, 255]);
+                }
+            }
+        }
+        if glyph.y_off < min_off_y {
+            min_off_y = glyph.y_off;
+        }
+
+        for character in &glyph.characters {
+            characters.insert(
+                *character,
+                CharacterData {
+                    id: *character as i32,
+                    x1: atlas_x as f32 / width_float,
+                    x2: (atlas_x + glyph.width) as f32 / width_float,
+                    y1: atlas_y as f32 / height_float,
+                    y2: (atlas_y + glyph.height) as f32 / height_float,
+                    width: glyph.width as i32,
+                    height: glyph.height as i32,
+                    x_off: glyph.x_off,
+                    y_off: glyph.y_off,
+                },
+            );
+        }
+    }
+
+    Ok(Font {
+        name,
+        image_buffer,
+        width,
+        height,
+        line_height,
+        size,
+        min_offset_y: min_off_y,
+        characters,
+        fallback_character: '?',
+        fallback_fonts: Vec::new(),
+        custom_glyphs: HashSet::new(),
+        truetype: None,
+        packer: None,
+        atlas_version: 0,
+        origin: FontOrigin(None),
+        sdf_spread: None,
+    })
+}
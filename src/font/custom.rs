@@ -0,0 +1,105 @@
+//! Custom glyphs (ie. game sprites or tiles) that are added to a Font at runtime.
+
+use std::char;
+
+use super::{CharacterData, Font, FontError};
+
+/// The first code point of the Unicode private use area, where custom glyphs are placed by default.
+const PRIVATE_USE_AREA_START: u32 = 0xE000;
+/// The last code point of the Unicode private use area.
+const PRIVATE_USE_AREA_END: u32 = 0xF8FF;
+
+impl Font {
+    /// Adds a custom glyph to the font from RGBA pixel data (`width * height * 4` bytes, row by row),
+    /// and returns the character it was mapped to. The character is the first unused code point in the
+    /// Unicode private use area (U+E000 - U+F8FF).
+    ///
+    /// The glyph is stretched to fill a whole cell of the `TextBuffer`, so it can be used for tiles. Example:
+    ///
+    /// ```
+    /// use glerminal::font::Font;
+    ///
+    /// let mut font = Font::load("fonts/iosevka.sfl");
+    /// let red_tile: Vec<u8> = (0..8 * 8).flat_map(|_| vec![255, 0, 0, 255]).collect();
+    /// let wall = font.add_custom_glyph((8, 8), &red_tile).unwrap();
+    /// assert_eq!(wall, '\u{E000}');
+    /// ```
+    ///
+    /// If the font is already used by a `Terminal`, the glyph can be added with
+    /// [`Terminal::get_font_mut`](../terminal/struct.Terminal.html#method.get_font_mut);
+    /// the atlas of a `TextBuffer` is updated the next time it is flushed.
+    pub fn add_custom_glyph(
+        &mut self,
+        dimensions: (u32, u32),
+        pixels: &[u8],
+    ) -> Result<char, FontError> {
+        let character = match (PRIVATE_USE_AREA_START..PRIVATE_USE_AREA_END + 1)
+            .filter_map(char::from_u32)
+            .find(|character| !self.characters.contains_key(character))
+        {
+            Some(character) => character,
+            None => return Err(FontError::PrivateUseAreaFull),
+        };
+        self.set_custom_glyph(character, dimensions, pixels)?;
+        Ok(character)
+    }
+
+    /// Sets the glyph of the given character to a custom glyph from RGBA pixel data (`width * height * 4` bytes, row by row),
+    /// replacing the existing glyph if there is one. See [`add_custom_glyph`](#method.add_custom_glyph).
+    pub fn set_custom_glyph(
+        &mut self,
+        character: char,
+        dimensions: (u32, u32),
+        pixels: &[u8],
+    ) -> Result<(), FontError> {
+        let (width, height) = dimensions;
+        let expected_size = (width * height * 4) as usize;
+        if pixels.len() != expected_size {
+            return Err(FontError::SizeMismatch {
+                expected: expected_size,
+                actual: pixels.len(),
+            });
+        }
+        if width == 0 || height == 0 {
+            return Err(FontError::InvalidCellDimensions(width, height));
+        }
+        self.place_custom_glyph(character, dimensions, pixels);
+        Ok(())
+    }
+
+    /// Packs a custom glyph into the atlas like [`set_custom_glyph`](#method.set_custom_glyph),
+    /// when the dimensions are known to be above 0 and to match the pixel data.
+    pub(crate) fn place_custom_glyph(
+        &mut self,
+        character: char,
+        dimensions: (u32, u32),
+        pixels: &[u8],
+    ) {
+        let (width, height) = dimensions;
+        let (atlas_x, atlas_y) = self.allocate_atlas_region(width, height);
+        self.custom_glyphs.insert(character);
+        let row_length = (width * 4) as usize;
+        for y in 0..height as usize {
+            let start = ((atlas_y as usize + y) * self.width as usize + atlas_x as usize) * 4;
+            self.image_buffer[start..start + row_length]
+                .copy_from_slice(&pixels[y * row_length..(y + 1) * row_length]);
+        }
+
+        let width_float = self.width as f32;
+        let height_float = self.height as f32;
+        self.characters.insert(
+            character,
+            CharacterData {
+                id: character as i32,
+                x1: atlas_x as f32 / width_float,
+                x2: (atlas_x + width) as f32 / width_float,
+                y1: atlas_y as f32 / height_float,
+                y2: (atlas_y + height) as f32 / height_float,
+                width: self.size as i32,
+                height: self.line_height as i32,
+                x_off: 0,
+                y_off: self.min_offset_y,
+            },
+        );
+    }
+}
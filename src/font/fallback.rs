@@ -0,0 +1,143 @@
+//! Fallback fonts, which provide the glyphs that are missing from a font.
+
+use std::mem;
+
+use super::{CharacterData, Font};
+
+impl Font {
+    /// Adds a fallback font, whose glyphs are used for characters that are missing from this font.
+    ///
+    /// Fallback fonts are used in the order they are added; a glyph is only taken from a fallback font if this font
+    /// and the fallback fonts added before it do not have it. Example:
+    ///
+    /// ```
+    /// use glerminal::font::{Font, GridMapping};
+    ///
+    /// let symbols = Font::from_grid("fonts/iosevka.png", (24, 23), GridMapping::Cp437).unwrap();
+    ///
+    /// let mut font = Font::load("fonts/iosevka.sfl");
+    /// font.add_fallback_font(symbols);
+    /// ```
+    ///
+    /// The glyphs of a fallback font are copied into this font's atlas the first time they are flushed to the screen,
+    /// and scaled to this font's size and line height, so a fallback font does not need to be the same size as the font
+    /// it's a fallback for. TrueType fallback fonts rasterize the glyphs they haven't loaded yet when they are first needed.
+    /// If this font is an SDF font, the copied glyphs are converted as well, but the fallback fonts themselves should not be SDF fonts.
+    pub fn add_fallback_font(&mut self, fallback: Font) {
+        self.fallback_fonts.push(fallback);
+    }
+
+    /// Adds a fallback font the same way as [`add_fallback_font`](#method.add_fallback_font), but in a builder-like fashion. Example:
+    ///
+    /// ```
+    /// use glerminal::terminal::TerminalBuilder;
+    /// use glerminal::font::{Font, GridMapping};
+    ///
+    /// let symbols = Font::from_grid("fonts/iosevka.png", (24, 23), GridMapping::Cp437).unwrap();
+    ///
+    /// let terminal = TerminalBuilder::new()
+    ///     .with_font(Font::load("fonts/iosevka.sfl").with_fallback_font(symbols))
+    ///     .with_headless(true)
+    ///     .build();
+    /// ```
+    pub fn with_fallback_font(mut self, fallback: Font) -> Font {
+        self.add_fallback_font(fallback);
+        self
+    }
+
+    /// Copies the glyph of the given character from the first fallback font that has it (or is able to load it) into this font.
+    /// Returns whether a fallback font had the glyph.
+    pub(crate) fn copy_fallback_glyph(&mut self, character: char) -> bool {
+        let mut fallback_fonts = mem::replace(&mut self.fallback_fonts, Vec::new());
+        let mut found = false;
+        for fallback in &mut fallback_fonts {
+            if fallback.load_glyph(character) {
+                self.copy_glyph(fallback, character);
+                found = true;
+                break;
+            }
+        }
+        self.fallback_fonts = fallback_fonts;
+        found
+    }
+
+    fn copy_glyph(&mut self, fallback: &Font, character: char) {
+        let character_data = fallback.characters[&character].clone();
+        let scale_x = self.size as f32 / fallback.size as f32;
+        let scale_y = self.line_height as f32 / fallback.line_height as f32;
+        let width = character_data.width as f32 * scale_x;
+        let height = character_data.height as f32 * scale_y;
+        let x_off = character_data.x_off as f32 * scale_x;
+        let y_off = (character_data.y_off - fallback.min_offset_y) as f32 * scale_y
+            + self.min_offset_y as f32;
+
+        let x1 = (character_data.x1 * fallback.width as f32).round() as u32;
+        let x2 = (character_data.x2 * fallback.width as f32).round() as u32;
+        let y1 = (character_data.y1 * fallback.height as f32).round() as u32;
+        let y2 = (character_data.y2 * fallback.height as f32).round() as u32;
+        let region_width = x2.saturating_sub(x1);
+        let region_height = y2.saturating_sub(y1);
+        if region_width == 0 || region_height == 0 {
+            self.characters.insert(
+                character,
+                CharacterData {
+                    id: character_data.id,
+                    x1: 0.0,
+                    x2: 0.0,
+                    y1: 0.0,
+                    y2: 0.0,
+                    width: width.round() as i32,
+                    height: height.round() as i32,
+                    x_off: x_off.round() as i32,
+                    y_off: y_off.round() as i32,
+                },
+            );
+            return;
+        }
+
+        let (atlas_x, atlas_y, packed_width, packed_height) = match self.sdf_spread {
+            Some(_) => {
+                let mut alpha = Vec::with_capacity((region_width * region_height) as usize);
+                for y in y1..y2 {
+                    for x in x1..x2 {
+                        let index = ((y * fallback.width + x) * 4 + 3) as usize;
+                        alpha.push(fallback.image_buffer[index]);
+                    }
+                }
+                self.pack_alpha_glyph(&alpha, region_width, region_height)
+            }
+            None => {
+                let (atlas_x, atlas_y) = self.allocate_atlas_region(region_width, region_height);
+                let row_length = (region_width * 4) as usize;
+                for y in 0..region_height {
+                    let old_start = (((y1 + y) * fallback.width + x1) * 4) as usize;
+                    let new_start = (((atlas_y + y) * self.width + atlas_x) * 4) as usize;
+                    self.image_buffer[new_start..new_start + row_length]
+                        .copy_from_slice(&fallback.image_buffer[old_start..old_start + row_length]);
+                }
+                (atlas_x, atlas_y, region_width, region_height)
+            }
+        };
+
+        // The SDF padding in the units of the glyph's metrics, which differ from the atlas for scaled glyphs
+        let spread = self.sdf_spread.unwrap_or(0) as f32;
+        let padding_x = spread * width / region_width as f32;
+        let padding_y = spread * height / region_height as f32;
+        let width_float = self.width as f32;
+        let height_float = self.height as f32;
+        self.characters.insert(
+            character,
+            CharacterData {
+                id: character_data.id,
+                x1: atlas_x as f32 / width_float,
+                x2: (atlas_x + packed_width) as f32 / width_float,
+                y1: atlas_y as f32 / height_float,
+                y2: (atlas_y + packed_height) as f32 / height_float,
+                width: (width + padding_x * 2.0).round() as i32,
+                height: (height + padding_y * 2.0).round() as i32,
+                x_off: (x_off - padding_x).round() as i32,
+                y_off: (y_off - padding_y).round() as i32,
+            },
+        );
+    }
+}
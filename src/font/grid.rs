@@ -0,0 +1,162 @@
+//! Loading of fonts from grid tilesets, such as the CP437 sprite sheets commonly used in roguelikes.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::Read;
+use std::path::PathBuf;
+
+use super::reload::{FontOrigin, FontSource};
+use super::{decode_png, CharacterData, Font, FontError};
+
+/// Code page 437, in the order the characters appear in a CP437 tileset.
+#[rustfmt::skip]
+pub(crate) static CP437: [char; 256] = [
+    '\u{0}', '☺', '☻', '♥', '♦', '♣', '♠', '•', '◘', '○', '◙', '♂', '♀', '♪', '♫', '☼',
+    '►', '◄', '↕', '‼', '¶', '§', '▬', '↨', '↑', '↓', '→', '←', '∟', '↔', '▲', '▼',
+    ' ', '!', '"', '#', '$', '%', '&', '\'', '(', ')', '*', '+', ',', '-', '.', '/',
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', ':', ';', '<', '=', '>', '?',
+    '@', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O',
+    'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z', '[', '\\', ']', '^', '_',
+    '`', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o',
+    'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z', '{', '|', '}', '~', '⌂',
+    'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å',
+    'É', 'æ', 'Æ', 'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ',
+    'á', 'í', 'ó', 'ú', 'ñ', 'Ñ', 'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»',
+    '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕', '╣', '║', '╗', '╝', '╜', '╛', '┐',
+    '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦', '╠', '═', '╬', '╧',
+    '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐', '▀',
+    'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩',
+    '≡', '±', '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{A0}',
+];
+
+/// Describes which character each cell of a grid tileset represents.
+///
+/// Cells are numbered left to right, top to bottom.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GridMapping {
+    /// The cells are in [code page 437](https://en.wikipedia.org/wiki/Code_page_437) order, as in most roguelike tilesets.
+    Cp437,
+    /// The cells are mapped to the given characters in order. Cells past the end of the list are ignored.
+    Custom(Vec<char>),
+}
+
+impl Default for GridMapping {
+    fn default() -> GridMapping {
+        GridMapping::Cp437
+    }
+}
+
+impl GridMapping {
+    fn get(&self, index: usize) -> Option<char> {
+        match *self {
+            GridMapping::Cp437 => CP437.get(index).cloned(),
+            GridMapping::Custom(ref characters) => characters.get(index).cloned(),
+        }
+    }
+}
+
+impl Font {
+    /// Loads a font from a grid tileset image, where every character is a cell of the given dimensions (width, height in pixels).
+    ///
+    /// No .sfl file is needed; the characters are mapped to the cells with the given [`GridMapping`](enum.GridMapping.html). Example:
+    ///
+    /// ```
+    /// use glerminal::font::{Font, GridMapping};
+    ///
+    /// let font = Font::from_grid("fonts/iosevka.png", (24, 23), GridMapping::Cp437).unwrap();
+    /// ```
+    pub fn from_grid<T: Into<PathBuf>>(
+        image_path: T,
+        cell_dimensions: (u32, u32),
+        mapping: GridMapping,
+    ) -> Result<Font, FontError> {
+        let image_path = image_path.into();
+        let name = match image_path.file_stem() {
+            Some(stem) => stem.to_string_lossy().into_owned(),
+            None => "Grid".to_owned(),
+        };
+        let mut font =
+            Font::from_grid_raw(File::open(&image_path)?, cell_dimensions, mapping.clone())?;
+        font.name = name;
+        font.origin = FontOrigin(Some(FontSource::Grid {
+            image_path,
+            cell_dimensions,
+            mapping,
+        }));
+        Ok(font)
+    }
+
+    /// Loads a font from a grid tileset image the same way as [`from_grid`](#method.from_grid), but from a Read (image read). Example:
+    ///
+    /// ```
+    /// use glerminal::font::{Font, GridMapping};
+    ///
+    /// static TILESET_PNG: &'static [u8] = include_bytes!("../../fonts/iosevka.png");
+    ///
+    /// let font = Font::from_grid_raw(TILESET_PNG, (24, 23), GridMapping::default()).unwrap();
+    /// ```
+    pub fn from_grid_raw<R: Read>(
+        image_read: R,
+        cell_dimensions: (u32, u32),
+        mapping: GridMapping,
+    ) -> Result<Font, FontError> {
+        let (cell_width, cell_height) = cell_dimensions;
+        let (image_buffer, width, height) = decode_png(image_read)?;
+
+        if cell_width == 0 || cell_height == 0 || cell_width > width || cell_height > height {
+            return Err(FontError::InvalidCellDimensions(cell_width, cell_height));
+        }
+
+        let columns = width / cell_width;
+        let rows = height / cell_height;
+
+        let width_float = width as f32;
+        let height_float = height as f32;
+        let mut characters = HashMap::<char, CharacterData>::new();
+        for row in 0..rows {
+            for column in 0..columns {
+                let index = (row * columns + column) as usize;
+                let character = match mapping.get(index) {
+                    Some(character) => character,
+                    None => continue,
+                };
+
+                let x = column * cell_width;
+                let y = row * cell_height;
+                characters.insert(
+                    character,
+                    CharacterData {
+                        id: character as i32,
+                        x1: x as f32 / width_float,
+                        x2: (x + cell_width) as f32 / width_float,
+                        y1: y as f32 / height_float,
+                        y2: (y + cell_height) as f32 / height_float,
+                        width: cell_width as i32,
+                        height: cell_height as i32,
+                        x_off: 0,
+                        y_off: 0,
+                    },
+                );
+            }
+        }
+
+        Ok(Font {
+            name: "Grid".to_owned(),
+            image_buffer,
+            width,
+            height,
+            line_height: cell_height,
+            size: cell_width,
+            min_offset_y: 0,
+            characters,
+            fallback_character: '?',
+            fallback_fonts: Vec::new(),
+            custom_glyphs: HashSet::new(),
+            truetype: None,
+            packer: None,
+            atlas_version: 0,
+            origin: FontOrigin(None),
+            sdf_spread: None,
+        })
+    }
+}
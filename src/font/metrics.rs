@@ -0,0 +1,131 @@
+//! Read-only queries for the metrics of a Font and its glyphs, for layout engines, custom renderers and the like.
+
+use std::collections::hash_map::Keys;
+
+use super::{CharacterData, Font};
+
+/// The metrics of a single glyph, in pixels of the font's atlas. See [`Font::get_glyph_metrics`](struct.Font.html#method.get_glyph_metrics).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GlyphMetrics {
+    /// How far the cursor moves after the glyph. The `TextBuffer` is a monospace grid, so this is always the size of the font.
+    pub advance: u32,
+    /// Offset (x, y) from the top-left corner of the cell to the top-left corner of the glyph's bounding box.
+    pub bearing: (i32, i32),
+    /// Dimensions (width, height) of the glyph's bounding box.
+    pub dimensions: (u32, u32),
+    /// Texture coordinates (x1, y1, x2, y2) of the glyph in the font's atlas, from 0.0 to 1.0.
+    pub uv_rect: (f32, f32, f32, f32),
+}
+
+/// An iterator over the characters that have a glyph in a Font, in no particular order.
+/// See [`Font::characters`](struct.Font.html#method.characters).
+pub struct Characters<'a> {
+    keys: Keys<'a, char, CharacterData>,
+}
+
+impl<'a> Iterator for Characters<'a> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        self.keys.next().cloned()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.keys.size_hint()
+    }
+}
+
+impl CharacterData {
+    /// Returns the id of the character in the font file it was loaded from.
+    pub fn get_id(&self) -> i32 {
+        self.id
+    }
+
+    /// Returns the dimensions (width, height) of the glyph in pixels.
+    pub fn get_dimensions(&self) -> (i32, i32) {
+        (self.width, self.height)
+    }
+
+    /// Returns the offset (x, y) of the glyph in pixels, as specified by the font file.
+    pub fn get_offset(&self) -> (i32, i32) {
+        (self.x_off, self.y_off)
+    }
+
+    /// Returns the texture coordinates (x1, y1, x2, y2) of the glyph in the font's atlas, from 0.0 to 1.0.
+    pub fn get_uv_rect(&self) -> (f32, f32, f32, f32) {
+        (self.x1, self.y1, self.x2, self.y2)
+    }
+}
+
+impl Font {
+    /// Returns whether the font has a glyph for the given character. Example:
+    ///
+    /// ```
+    /// use glerminal::font::Font;
+    ///
+    /// let font = Font::load("fonts/iosevka.sfl");
+    /// assert!(font.has_glyph('a'));
+    /// ```
+    ///
+    /// TrueType fonts only have glyphs for printable ASCII characters and the characters they have been used to draw so far.
+    pub fn has_glyph(&self, character: char) -> bool {
+        self.characters.contains_key(&character)
+    }
+
+    /// Returns the metrics of the glyph of the given character, or `None` if the font has no glyph for it. Example:
+    ///
+    /// ```
+    /// use glerminal::font::Font;
+    ///
+    /// let font = Font::load("fonts/iosevka.sfl");
+    /// let metrics = font.get_glyph_metrics('a').unwrap();
+    /// assert_eq!(metrics.advance, font.size);
+    /// ```
+    pub fn get_glyph_metrics(&self, character: char) -> Option<GlyphMetrics> {
+        self.characters
+            .get(&character)
+            .map(|character_data| GlyphMetrics {
+                advance: self.size,
+                bearing: (
+                    character_data.x_off,
+                    character_data.y_off - self.min_offset_y,
+                ),
+                dimensions: (
+                    character_data.width.max(0) as u32,
+                    character_data.height.max(0) as u32,
+                ),
+                uv_rect: character_data.get_uv_rect(),
+            })
+    }
+
+    /// Returns an iterator over the characters that have a glyph in the font, in no particular order. Example:
+    ///
+    /// ```
+    /// use glerminal::font::Font;
+    ///
+    /// let font = Font::load("fonts/iosevka.sfl");
+    /// let mut characters: Vec<char> = font.characters().collect();
+    /// characters.sort();
+    /// ```
+    pub fn characters(&self) -> Characters {
+        Characters {
+            keys: self.characters.keys(),
+        }
+    }
+
+    /// Returns the smallest vertical offset of the glyphs, as specified by the font file.
+    /// Glyphs are drawn this much higher than their offsets say, so that the highest glyph touches the top of the cell.
+    pub fn get_min_offset_y(&self) -> i32 {
+        self.min_offset_y
+    }
+
+    /// Returns the dimensions (width, height) of the font's atlas in pixels.
+    pub fn get_atlas_dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Returns the RGBA pixel data of the font's atlas, row by row.
+    pub fn get_atlas_image(&self) -> &[u8] {
+        &self.image_buffer
+    }
+}
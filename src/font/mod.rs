@@ -0,0 +1,555 @@
+//! This module is used to load fonts that can be used in the [`TextBuffer`](text_buffer/struct.TextBuffer.html)
+//!
+//! The [`Font`](struct.Font.html) can be loaded from an `.sfl` file and then used in the `TextBuffer`, in example:
+//! ```
+//! use glerminal::terminal::TerminalBuilder;
+//! use glerminal::font::Font;
+//!
+//! let mut terminal = TerminalBuilder::new()
+//!     .with_title("Hello glerminal::font::Font!")
+//!     .with_dimensions((1280, 720))
+//!     .with_font(Font::load("fonts/iosevka.sfl"))
+//!     .with_headless(true)
+//!     .build();
+//! ```
+//!
+//! Alternatively you can use `load_raw` to load the font straight with `include_str!` and `include_bytes!`, example:
+//! ```
+//! use glerminal::terminal::TerminalBuilder;
+//! use glerminal::font::Font;
+//!
+//! static IOSEVKA_SFL: &'static str = include_str!("../../fonts/iosevka.sfl");
+//! static IOSEVKA_PNG: &'static [u8] = include_bytes!("../../fonts/iosevka.png");
+//!
+//! let mut terminal = TerminalBuilder::new()
+//!     .with_title("Hello glerminal::font::Font!")
+//!     .with_dimensions((1280, 720))
+//!     .with_font(Font::load_raw(IOSEVKA_SFL, IOSEVKA_PNG))
+//!     .with_headless(true)
+//!     .build();
+//! ```
+//!
+//! Both `load` and `load_raw` panic if the font could not be loaded. If you are loading fonts that might be broken (ie. user-supplied fonts),
+//! use [`try_load`](struct.Font.html#method.try_load) or [`try_load_raw`](struct.Font.html#method.try_load_raw) instead,
+//! which return a [`FontError`](enum.FontError.html) on failure.
+//!
+//! The font image can be a PNG of any color type (RGBA, RGB, grayscale, grayscale with alpha or indexed);
+//! it is converted into RGBA when loaded. Grayscale images without alpha are treated as white glyphs that use the luminance as alpha.
+//!
+//! Fonts can also be loaded from grid tilesets (such as the CP437 sprite sheets used by many roguelikes) without an `.sfl` file,
+//! with [`from_grid`](struct.Font.html#method.from_grid) and [`from_grid_raw`](struct.Font.html#method.from_grid_raw):
+//! ```
+//! use glerminal::terminal::TerminalBuilder;
+//! use glerminal::font::{Font, GridMapping};
+//!
+//! let mut terminal = TerminalBuilder::new()
+//!     .with_title("Hello glerminal::font::Font!")
+//!     .with_dimensions((1280, 720))
+//!     .with_font(Font::from_grid("fonts/iosevka.png", (24, 23), GridMapping::Cp437).unwrap())
+//!     .with_headless(true)
+//!     .build();
+//! ```
+//!
+//! TrueType and OpenType fonts can be used directly with [`load_truetype`](struct.Font.html#method.load_truetype);
+//! the glyphs are rasterized into the font's atlas at the given pixel size when they are first needed:
+//! ```no_run
+//! use glerminal::terminal::TerminalBuilder;
+//! use glerminal::font::Font;
+//!
+//! let mut terminal = TerminalBuilder::new()
+//!     .with_title("Hello glerminal::font::Font!")
+//!     .with_dimensions((1280, 720))
+//!     .with_font(Font::load_truetype("fonts/SourceCodePro-Medium.ttf", 24))
+//!     .build();
+//! ```
+//!
+//! Linux console fonts (PSF1 and PSF2) and X11 bitmap fonts (BDF) can be loaded with [`from_psf`](struct.Font.html#method.from_psf)
+//! and [`from_bdf`](struct.Font.html#method.from_bdf) respectively.
+//!
+//! Characters that are missing from a font can be taken from other fonts, see [`add_fallback_font`](struct.Font.html#method.add_fallback_font).
+//!
+//! The metrics of the glyphs can be queried with [`get_glyph_metrics`](struct.Font.html#method.get_glyph_metrics),
+//! ie. for custom layout or rendering.
+//!
+//! Fonts can be converted into signed distance field fonts with [`with_sdf`](struct.Font.html#method.with_sdf),
+//! which stay crisp at any scale.
+//!
+//! Custom glyphs, like game sprites, can be added to a font at runtime from RGBA pixel data, see [`add_custom_glyph`](struct.Font.html#method.add_custom_glyph).
+
+use png::{BitDepth, ColorType, Decoder, DecodingError};
+use std::io::{self, Read};
+use std::fs::File;
+use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+
+use sfl_parser::BMFont;
+
+mod atlas;
+mod bitmap;
+mod custom;
+mod fallback;
+mod grid;
+mod metrics;
+mod reload;
+mod sdf;
+mod truetype;
+
+pub use self::grid::GridMapping;
+pub use self::metrics::{Characters, GlyphMetrics};
+use self::atlas::AtlasPacker;
+pub(crate) use self::reload::FontWatcher;
+use self::reload::{FontOrigin, FontSource};
+use self::truetype::TrueTypeRasterizer;
+
+/// Represents an error that occurred while loading a [`Font`](struct.Font.html)
+#[derive(Debug)]
+pub enum FontError {
+    /// Reading the .sfl or image file failed
+    Io(io::Error),
+    /// The .sfl file could not be parsed
+    Sfl(String),
+    /// The font image could not be decoded as a PNG
+    Png(DecodingError),
+    /// The font image has a color type that is not supported
+    UnsupportedColorType(ColorType),
+    /// The decoded font image was not the size it should be according to its dimensions
+    SizeMismatch {
+        /// The size (in bytes) the image should have been
+        expected: usize,
+        /// The size (in bytes) the image actually was
+        actual: usize,
+    },
+    /// The cell dimensions given for a grid font were zero or larger than the image
+    InvalidCellDimensions(u32, u32),
+    /// The TrueType/OpenType font could not be loaded
+    TrueType(String),
+    /// The PSF font could not be parsed
+    Psf(String),
+    /// The BDF font could not be parsed
+    Bdf(String),
+    /// There are no unused code points left in the Unicode private use area for a custom glyph
+    PrivateUseAreaFull,
+}
+
+impl fmt::Display for FontError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FontError::Io(ref error) => write!(f, "IO error: {}", error),
+            FontError::Sfl(ref error) => write!(f, "Failed to parse .sfl file: {}", error),
+            FontError::Png(ref error) => write!(f, "Failed to decode font image: {}", error),
+            FontError::UnsupportedColorType(ref color_type) => {
+                write!(
+                    f,
+                    "Font image color type is not supported: {:?}",
+                    color_type
+                )
+            }
+            FontError::SizeMismatch { expected, actual } => write!(
+                f,
+                "Font image is deformed: expected {} bytes, got {}",
+                expected, actual
+            ),
+            FontError::InvalidCellDimensions(width, height) => {
+                write!(f, "Invalid grid cell dimensions: {}x{}", width, height)
+            }
+            FontError::TrueType(ref error) => write!(f, "Failed to load TrueType font: {}", error),
+            FontError::Psf(ref error) => write!(f, "Failed to parse PSF font: {}", error),
+            FontError::Bdf(ref error) => write!(f, "Failed to parse BDF font: {}", error),
+            FontError::PrivateUseAreaFull => {
+                write!(f, "No unused code points left in the private use area")
+            }
+        }
+    }
+}
+
+impl Error for FontError {
+    fn description(&self) -> &str {
+        match *self {
+            FontError::Io(_) => "IO error",
+            FontError::Sfl(_) => "failed to parse .sfl file",
+            FontError::Png(_) => "failed to decode font image",
+            FontError::UnsupportedColorType(_) => "font image color type is not supported",
+            FontError::SizeMismatch { .. } => "font image is deformed",
+            FontError::InvalidCellDimensions(..) => "invalid grid cell dimensions",
+            FontError::TrueType(_) => "failed to load TrueType font",
+            FontError::Psf(_) => "failed to parse PSF font",
+            FontError::Bdf(_) => "failed to parse BDF font",
+            FontError::PrivateUseAreaFull => "no unused code points left in the private use area",
+        }
+    }
+}
+
+impl From<io::Error> for FontError {
+    fn from(error: io::Error) -> FontError {
+        FontError::Io(error)
+    }
+}
+
+impl From<DecodingError> for FontError {
+    fn from(error: DecodingError) -> FontError {
+        FontError::Png(error)
+    }
+}
+
+/// Contains data of a single character in a Font
+#[derive(Debug, Clone, PartialEq)]
+pub struct CharacterData {
+    pub(crate) id: i32,
+    pub(crate) x1: f32,
+    pub(crate) x2: f32,
+    pub(crate) y1: f32,
+    pub(crate) y2: f32,
+    pub(crate) width: i32,
+    pub(crate) height: i32,
+    pub(crate) x_off: i32,
+    pub(crate) y_off: i32,
+}
+
+/// Represents the font when it's loaded.
+#[derive(Debug)]
+pub struct Font {
+    /// The name of the font
+    pub name: String,
+    pub(crate) image_buffer: Vec<u8>,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    /// Line height of the font
+    pub line_height: u32,
+    /// Size of the font (width)
+    pub size: u32,
+    pub(crate) min_offset_y: i32,
+    pub(crate) characters: HashMap<char, CharacterData>,
+    pub(crate) fallback_character: char,
+    /// Fonts that provide the glyphs that are missing from this font, in the order they are used
+    pub(crate) fallback_fonts: Vec<Font>,
+    /// Characters whose glyphs were set with `set_custom_glyph`, which are kept when the font is hot-reloaded
+    pub(crate) custom_glyphs: HashSet<char>,
+    pub(crate) truetype: Option<TrueTypeRasterizer>,
+    pub(crate) packer: Option<AtlasPacker>,
+    /// Incremented every time the atlas changes after loading, so meshes know to re-upload their texture
+    pub(crate) atlas_version: u32,
+    /// The files the font was loaded from, used for hot-reloading
+    pub(crate) origin: FontOrigin,
+    /// The spread (in pixels) of the signed distance field, if the atlas is one
+    pub(crate) sdf_spread: Option<u32>,
+}
+
+impl PartialEq for Font {
+    // The origin is left out, so a Font loaded from files equals the same Font loaded from memory.
+    fn eq(&self, other: &Font) -> bool {
+        self.name == other.name
+            && self.image_buffer == other.image_buffer
+            && self.width == other.width
+            && self.height == other.height
+            && self.line_height == other.line_height
+            && self.size == other.size
+            && self.min_offset_y == other.min_offset_y
+            && self.characters == other.characters
+            && self.fallback_character == other.fallback_character
+            && self.fallback_fonts == other.fallback_fonts
+            && self.custom_glyphs == other.custom_glyphs
+            && self.truetype == other.truetype
+            && self.packer == other.packer
+            && self.atlas_version == other.atlas_version
+            && self.sdf_spread == other.sdf_spread
+    }
+}
+
+impl Font {
+    /// Loads the font fron the given .sfl file, for example:
+    ///
+    /// ```
+    /// use glerminal::font::Font;
+    /// let font = Font::load("fonts/iosevka.sfl");
+    /// ```
+    ///
+    /// Panics if the font could not be loaded, see [`try_load`](#method.try_load) for a non-panicking version.
+    pub fn load<T: Into<PathBuf>>(fnt_path: T) -> Font {
+        match Font::try_load(fnt_path) {
+            Ok(font) => font,
+            Err(error) => panic!("Failed to load font: {}", error),
+        }
+    }
+
+    /// Loads the font from the given string (.sfl file contents) and Read (image read)
+    ///
+    /// ```
+    /// use glerminal::font::Font;
+    /// use std::fs::File;
+    ///
+    /// let font = Font::load_raw(include_str!("../../fonts/iosevka.sfl"), File::open("fonts/iosevka.png").unwrap());
+    /// ```
+    ///
+    /// Panics if the font could not be loaded, see [`try_load_raw`](#method.try_load_raw) for a non-panicking version.
+    pub fn load_raw<T: Into<String>, R: Read>(sfl_content: T, image_read: R) -> Font {
+        match Font::try_load_raw(sfl_content, image_read) {
+            Ok(font) => font,
+            Err(error) => panic!("Failed to load font: {}", error),
+        }
+    }
+
+    /// Loads the font from the given .sfl file, returning a [`FontError`](enum.FontError.html) if the loading fails. Example:
+    ///
+    /// ```
+    /// use glerminal::font::Font;
+    ///
+    /// match Font::try_load("fonts/iosevka.sfl") {
+    ///     Ok(font) => println!("Loaded font {}", font.name),
+    ///     Err(error) => println!("Failed to load font: {}", error),
+    /// }
+    /// ```
+    pub fn try_load<T: Into<PathBuf>>(fnt_path: T) -> Result<Font, FontError> {
+        let fnt_path = fnt_path.into();
+        if !fnt_path.exists() {
+            return Err(FontError::Io(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Font file not found: {}", fnt_path.display()),
+            )));
+        }
+        // Load Font .sfl file
+        let bm_font = match BMFont::from_path(fnt_path.clone()) {
+            Ok(bmf) => bmf,
+            Err(error) => return Err(FontError::Sfl(error.to_string())),
+        };
+
+        // Load Font image file
+        let image_read = File::open(&bm_font.image_path)?;
+        let mut font = Font::load_with_bmfont_and_image_read(&bm_font, image_read)?;
+        font.origin = FontOrigin(Some(FontSource::Sfl {
+            sfl_path: fnt_path,
+            image_path: PathBuf::from(&bm_font.image_path),
+        }));
+        Ok(font)
+    }
+
+    /// Loads the font from the given string (.sfl file contents) and Read (image read),
+    /// returning a [`FontError`](enum.FontError.html) if the loading fails.
+    pub fn try_load_raw<T: Into<String>, R: Read>(
+        sfl_content: T,
+        image_read: R,
+    ) -> Result<Font, FontError> {
+        let bm_font = match BMFont::from_loaded(sfl_content.into(), "image.png".to_owned()) {
+            Ok(bmf) => bmf,
+            Err(error) => return Err(FontError::Sfl(error.to_string())),
+        };
+
+        Font::load_with_bmfont_and_image_read(&bm_font, image_read)
+    }
+
+    fn load_with_bmfont_and_image_read<R: Read>(
+        bm_font: &BMFont,
+        read: R,
+    ) -> Result<Font, FontError> {
+        let (image_buffer, width, height) = decode_png(read)?;
+
+        // Load the font
+        let mut characters = HashMap::<char, CharacterData>::new();
+        let width_float = width as f32;
+        let height_float = height as f32;
+        let mut min_off_y = 100_000;
+        for (key, value) in bm_font.chars.iter() {
+            let character = match char::from_u32(*key as u32) {
+                Some(character) => character,
+                None => continue,
+            };
+            let x1 = value.x as f32 / width_float;
+            let x2 = (value.x as f32 + value.width as f32) / width_float;
+            let y1 = value.y as f32 / height_float;
+            let y2 = (value.y as f32 + value.height as f32) / height_float;
+            if value.yoffset < min_off_y {
+                min_off_y = value.yoffset;
+            }
+
+            characters.insert(
+                character,
+                CharacterData {
+                    id: value.id,
+                    x1,
+                    x2,
+                    y1,
+                    y2,
+                    width: value.width,
+                    height: value.height,
+                    x_off: value.xoffset,
+                    y_off: value.yoffset,
+                },
+            );
+        }
+
+        Ok(Font {
+            name: (&bm_font.font_name).clone(),
+            image_buffer: image_buffer,
+            width: width,
+            height: height,
+            line_height: bm_font.line_height,
+            size: bm_font.size,
+            min_offset_y: min_off_y,
+            characters: characters,
+            fallback_character: '?',
+            fallback_fonts: Vec::new(),
+            custom_glyphs: HashSet::new(),
+            truetype: None,
+            packer: None,
+            atlas_version: 0,
+            origin: FontOrigin(None),
+            sdf_spread: None,
+        })
+    }
+    /// Gets the CharacterData from the Font with the given char, if the charcter exists, otherwise returns an error as a String. Example:
+    ///
+    /// ```
+    /// use glerminal::font::Font;
+    /// let a_char_data = Font::load("fonts/iosevka.sfl").get_character('a');
+    /// ```
+    pub fn get_character(&self, character: char) -> Result<CharacterData, String> {
+        if let Some(character_data) = self.characters.get(&character) {
+            Ok(character_data.clone())
+        } else {
+            Err(format!(
+                "Character not found: '{}' (U+{:04X})",
+                character, character as u32
+            ))
+        }
+    }
+
+    /// Sets the character that is drawn in place of characters that are missing from the Font. Defaults to `'?'`.
+    ///
+    /// ```
+    /// use glerminal::font::Font;
+    /// let mut font = Font::load("fonts/iosevka.sfl");
+    /// font.set_fallback_character('#');
+    /// ```
+    pub fn set_fallback_character(&mut self, character: char) {
+        self.fallback_character = character;
+    }
+
+    /// Returns the character that is drawn in place of characters that are missing from the Font.
+    pub fn get_fallback_character(&self) -> char {
+        self.fallback_character
+    }
+
+    /// Resizes the atlas to the given dimensions, keeping the existing glyphs where they are.
+    /// The atlas can only grow; the new dimensions must be at least the current dimensions.
+    fn resize_atlas(&mut self, new_width: u32, new_height: u32) {
+        if new_width == self.width && new_height == self.height {
+            return;
+        }
+        let width_ratio = self.width as f32 / new_width as f32;
+        let height_ratio = self.height as f32 / new_height as f32;
+        for character_data in self.characters.values_mut() {
+            character_data.x1 *= width_ratio;
+            character_data.x2 *= width_ratio;
+            character_data.y1 *= height_ratio;
+            character_data.y2 *= height_ratio;
+        }
+
+        let mut image_buffer = vec![0; (new_width * new_height * 4) as usize];
+        let row_length = (self.width * 4) as usize;
+        for y in 0..self.height as usize {
+            let old_start = y * row_length;
+            let new_start = y * (new_width * 4) as usize;
+            image_buffer[new_start..new_start + row_length]
+                .copy_from_slice(&self.image_buffer[old_start..old_start + row_length]);
+        }
+        self.image_buffer = image_buffer;
+        self.width = new_width;
+        self.height = new_height;
+        self.atlas_version = self.atlas_version.wrapping_add(1);
+    }
+
+    /// Loads the glyphs of the given characters that are not in the Font yet, see [`load_glyph`](#method.load_glyph).
+    /// If some of them can't be loaded, the glyph of the fallback character is loaded instead.
+    pub(crate) fn load_missing_characters<I: Iterator<Item = char>>(&mut self, characters: I) {
+        let mut missing = false;
+        for character in characters {
+            if !self.load_glyph(character) {
+                missing = true;
+            }
+        }
+        if missing {
+            let fallback_character = self.fallback_character;
+            self.load_glyph(fallback_character);
+        }
+    }
+
+    /// Loads the glyph of the given character if it is not in the Font yet, by rasterizing it if the Font was loaded from
+    /// a TrueType font, or by copying it from the first fallback font that has it otherwise.
+    /// Returns whether the Font has the glyph afterwards.
+    pub(crate) fn load_glyph(&mut self, character: char) -> bool {
+        self.characters.contains_key(&character)
+            || self.rasterize_missing_character(character)
+            || self.copy_fallback_glyph(character)
+    }
+
+    /// Gets the CharacterData of the given char, or the CharacterData of the fallback character if the given char does not exist.
+    /// Returns `None` if neither of them exist in the Font.
+    ///
+    /// The glyphs of fallback fonts are only found after [`load_missing_characters`](#method.load_missing_characters)
+    /// has copied them into the Font.
+    pub(crate) fn get_character_or_fallback(&self, character: char) -> Option<&CharacterData> {
+        self.characters
+            .get(&character)
+            .or_else(|| self.characters.get(&self.fallback_character))
+    }
+}
+
+/// Decodes the given PNG image into an RGBA buffer, returning the buffer and the dimensions of the image.
+pub(crate) fn decode_png<R: Read>(read: R) -> Result<(Vec<u8>, u32, u32), FontError> {
+    let decoder = Decoder::new(read);
+    let (info, mut reader) = decoder.read_info()?;
+
+    // The decoder expands indexed and low bit depth images and strips 16-bit images to 8 bits per sample,
+    // so only the 8-bit variants of the remaining color types need to be handled here.
+    if info.bit_depth != BitDepth::Eight {
+        return Err(FontError::UnsupportedColorType(info.color_type));
+    }
+
+    let mut decoded_buffer = vec![0; info.buffer_size()];
+
+    reader.next_frame(&mut decoded_buffer)?;
+
+    let expected_size = (info.width * info.height) as usize * info.color_type.samples();
+    if decoded_buffer.len() != expected_size {
+        return Err(FontError::SizeMismatch {
+            expected: expected_size,
+            actual: decoded_buffer.len(),
+        });
+    }
+
+    let image_buffer = convert_to_rgba(info.color_type, decoded_buffer)?;
+    Ok((image_buffer, info.width, info.height))
+}
+
+/// Converts a decoded 8-bit image buffer of the given color type into RGBA.
+///
+/// Grayscale images without an alpha channel are treated as white glyphs, where the luminance is used as the alpha.
+fn convert_to_rgba(color_type: ColorType, buffer: Vec<u8>) -> Result<Vec<u8>, FontError> {
+    match color_type {
+        ColorType::RGBA => Ok(buffer),
+        ColorType::RGB => {
+            let mut rgba = Vec::with_capacity(buffer.len() / 3 * 4);
+            for pixel in buffer.chunks(3) {
+                rgba.extend_from_slice(pixel);
+                rgba.push(255);
+            }
+            Ok(rgba)
+        }
+        ColorType::GrayscaleAlpha => {
+            let mut rgba = Vec::with_capacity(buffer.len() * 2);
+            for pixel in buffer.chunks(2) {
+                rgba.extend_from_slice(&[pixel[0], pixel[0], pixel[0], pixel[1]]);
+            }
+            Ok(rgba)
+        }
+        ColorType::Grayscale => {
+            let mut rgba = Vec::with_capacity(buffer.len() * 4);
+            for luminance in buffer {
+                rgba.extend_from_slice(&[255, 255, 255, luminance]);
+            }
+            Ok(rgba)
+        }
+        ColorType::Indexed => Err(FontError::UnsupportedColorType(color_type)),
+    }
+}
@@ -0,0 +1,133 @@
+//! Keeps track of the files a Font was loaded from, so that the Font can be reloaded when they change.
+
+use std::fs;
+use std::mem;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use super::{Font, FontError, GridMapping};
+
+/// The files (and settings) a Font was loaded from.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum FontSource {
+    Sfl {
+        sfl_path: PathBuf,
+        image_path: PathBuf,
+    },
+    Grid {
+        image_path: PathBuf,
+        cell_dimensions: (u32, u32),
+        mapping: GridMapping,
+    },
+    TrueType {
+        font_path: PathBuf,
+        pixel_size: u32,
+    },
+    Psf(PathBuf),
+    Bdf(PathBuf),
+}
+
+impl FontSource {
+    fn paths(&self) -> Vec<PathBuf> {
+        match *self {
+            FontSource::Sfl {
+                ref sfl_path,
+                ref image_path,
+            } => vec![sfl_path.clone(), image_path.clone()],
+            FontSource::Grid { ref image_path, .. } => vec![image_path.clone()],
+            FontSource::TrueType { ref font_path, .. } => vec![font_path.clone()],
+            FontSource::Psf(ref path) | FontSource::Bdf(ref path) => vec![path.clone()],
+        }
+    }
+
+    fn load(&self) -> Result<Font, FontError> {
+        match *self {
+            FontSource::Sfl { ref sfl_path, .. } => Font::try_load(sfl_path.clone()),
+            FontSource::Grid {
+                ref image_path,
+                cell_dimensions,
+                ref mapping,
+            } => Font::from_grid(image_path.clone(), cell_dimensions, mapping.clone()),
+            FontSource::TrueType {
+                ref font_path,
+                pixel_size,
+            } => Font::try_load_truetype(font_path.clone(), pixel_size),
+            FontSource::Psf(ref path) => Font::from_psf(path.clone()),
+            FontSource::Bdf(ref path) => Font::from_bdf(path.clone()),
+        }
+    }
+}
+
+/// Where a Font was loaded from, if it was loaded from files.
+///
+/// This does not affect the equality of Fonts; a Font loaded from a file equals the same Font loaded from memory.
+#[derive(Debug, Clone)]
+pub(crate) struct FontOrigin(pub(crate) Option<FontSource>);
+
+impl Font {
+    /// Carries the glyphs that were added to the given Font at runtime over to this Font, which was reloaded from its files:
+    /// the fallback fonts are moved over, and the custom glyphs are copied from the atlas of the given Font.
+    pub(crate) fn keep_runtime_glyphs(&mut self, old_font: &mut Font) {
+        let mut custom_glyphs: Vec<char> = old_font.custom_glyphs.iter().cloned().collect();
+        custom_glyphs.sort();
+        for character in custom_glyphs {
+            let character_data = &old_font.characters[&character];
+            let x1 = (character_data.x1 * old_font.width as f32).round() as u32;
+            let x2 = (character_data.x2 * old_font.width as f32).round() as u32;
+            let y1 = (character_data.y1 * old_font.height as f32).round() as u32;
+            let y2 = (character_data.y2 * old_font.height as f32).round() as u32;
+            if x2 <= x1 || y2 <= y1 {
+                continue;
+            }
+
+            let row_length = ((x2 - x1) * 4) as usize;
+            let mut pixels = Vec::with_capacity(row_length * (y2 - y1) as usize);
+            for y in y1..y2 {
+                let start = ((y * old_font.width + x1) * 4) as usize;
+                pixels.extend_from_slice(&old_font.image_buffer[start..start + row_length]);
+            }
+            self.place_custom_glyph(character, (x2 - x1, y2 - y1), &pixels);
+        }
+
+        self.fallback_fonts = mem::replace(&mut old_font.fallback_fonts, Vec::new());
+    }
+}
+
+/// Watches the files a Font was loaded from, and reloads the Font when they are modified.
+pub(crate) struct FontWatcher {
+    source: FontSource,
+    modified: Vec<Option<(SystemTime, u64)>>,
+}
+
+impl FontWatcher {
+    /// Creates a watcher for the given Font, or returns `None` if the Font was not loaded from files.
+    pub(crate) fn new(font: &Font) -> Option<FontWatcher> {
+        font.origin.0.as_ref().map(|source| FontWatcher {
+            modified: FontWatcher::modification_times(source),
+            source: source.clone(),
+        })
+    }
+
+    /// Checks whether the files of the Font have been modified since the last check, and reloads the Font if they have.
+    pub(crate) fn poll(&mut self) -> Option<Result<Font, FontError>> {
+        let modified = FontWatcher::modification_times(&self.source);
+        if modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        Some(self.source.load())
+    }
+
+    /// Returns the modification time and length of each file, or `None` for files that can't be read (ie. are being written)
+    fn modification_times(source: &FontSource) -> Vec<Option<(SystemTime, u64)>> {
+        source
+            .paths()
+            .iter()
+            .map(|path| {
+                fs::metadata(path)
+                    .and_then(|meta| Ok((meta.modified()?, meta.len())))
+                    .ok()
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,147 @@
+//! Signed distance field fonts, which render crisp glyphs at any scale.
+
+use std::mem;
+
+use super::atlas::{AtlasPacker, GLYPH_PADDING};
+use super::{CharacterData, Font};
+
+impl Font {
+    /// Converts the font into a signed distance field (SDF) font, with the given spread in pixels. Example:
+    ///
+    /// ```
+    /// use glerminal::font::{Font, GridMapping};
+    ///
+    /// let font = Font::from_grid("fonts/iosevka.png", (24, 23), GridMapping::Cp437).unwrap().with_sdf(4);
+    /// assert!(font.is_sdf());
+    /// ```
+    ///
+    /// Instead of the coverage of each pixel, the alpha channel of an SDF atlas stores the distance to the edge of the glyph,
+    /// which the `Terminal` uses to draw sharp edges no matter how much the glyphs are scaled up or down.
+    /// The spread is how far (in pixels of the atlas) from the edge distances are stored; glyphs are padded by it.
+    ///
+    /// The quality of the result depends on the resolution of the source glyphs, so convert high-resolution bitmap fonts,
+    /// or TrueType fonts loaded with a large pixel size (ie. `Font::load_truetype("font.ttf", 64).with_sdf(8)`).
+    /// Glyphs rasterized later by a TrueType font or copied from [fallback fonts](#method.add_fallback_font) are converted
+    /// as well, but glyphs added with [`add_custom_glyph`](#method.add_custom_glyph) afterwards are not.
+    /// Converting a font that is already an SDF font does nothing.
+    pub fn with_sdf(mut self, spread: u32) -> Font {
+        if self.sdf_spread.is_some() {
+            return self;
+        }
+        let spread = spread.max(1);
+
+        let old_width = self.width;
+        let old_height = self.height;
+        let old_image_buffer = mem::replace(&mut self.image_buffer, Vec::new());
+        let mut old_characters: Vec<(char, CharacterData)> = self.characters.drain().collect();
+        old_characters.sort_by_key(|&(character, _)| character);
+
+        self.width = (self.size + spread * 2 + GLYPH_PADDING) * 16;
+        self.height = 0;
+        self.packer = Some(AtlasPacker::new(0));
+        self.sdf_spread = Some(spread);
+
+        for (character, character_data) in old_characters {
+            let x1 = (character_data.x1 * old_width as f32).round() as u32;
+            let x2 = (character_data.x2 * old_width as f32).round() as u32;
+            let y1 = (character_data.y1 * old_height as f32).round() as u32;
+            let y2 = (character_data.y2 * old_height as f32).round() as u32;
+            let region_width = x2.saturating_sub(x1);
+            let region_height = y2.saturating_sub(y1);
+            if region_width == 0 || region_height == 0 {
+                self.characters.insert(
+                    character,
+                    CharacterData {
+                        x1: 0.0,
+                        x2: 0.0,
+                        y1: 0.0,
+                        y2: 0.0,
+                        ..character_data
+                    },
+                );
+                continue;
+            }
+
+            let mut alpha = Vec::with_capacity((region_width * region_height) as usize);
+            for y in y1..y2 {
+                for x in x1..x2 {
+                    alpha.push(old_image_buffer[((y * old_width + x) * 4 + 3) as usize]);
+                }
+            }
+            let (atlas_x, atlas_y, sdf_width, sdf_height) =
+                self.pack_alpha_glyph(&alpha, region_width, region_height);
+
+            // The padding in the units of the glyph's metrics, which differ from the atlas for scaled fallback glyphs
+            let padding_x = spread as f32 * character_data.width as f32 / region_width as f32;
+            let padding_y = spread as f32 * character_data.height as f32 / region_height as f32;
+            let width_float = self.width as f32;
+            let height_float = self.height as f32;
+            self.characters.insert(
+                character,
+                CharacterData {
+                    id: character_data.id,
+                    x1: atlas_x as f32 / width_float,
+                    x2: (atlas_x + sdf_width) as f32 / width_float,
+                    y1: atlas_y as f32 / height_float,
+                    y2: (atlas_y + sdf_height) as f32 / height_float,
+                    width: (character_data.width as f32 + padding_x * 2.0).round() as i32,
+                    height: (character_data.height as f32 + padding_y * 2.0).round() as i32,
+                    x_off: (character_data.x_off as f32 - padding_x).round() as i32,
+                    y_off: (character_data.y_off as f32 - padding_y).round() as i32,
+                },
+            );
+        }
+
+        self.atlas_version = self.atlas_version.wrapping_add(1);
+        self
+    }
+
+    /// Returns whether the font is a signed distance field font, see [`with_sdf`](#method.with_sdf).
+    pub fn is_sdf(&self) -> bool {
+        self.sdf_spread.is_some()
+    }
+}
+
+/// Generates a signed distance field from the given alpha values (`width * height` bytes, row by row).
+///
+/// The field is padded by the spread on every side, so it is `(width + spread * 2) * (height + spread * 2)` bytes.
+/// The edge of the glyph lies at the value 128; the values grow towards 255 inside the glyph and fall towards 0 outside.
+pub(crate) fn generate_sdf(alpha: &[u8], width: u32, height: u32, spread: u32) -> Vec<u8> {
+    let spread = spread as i32;
+    let width = width as i32;
+    let height = height as i32;
+    let is_inside = |x: i32, y: i32| {
+        x >= 0 && y >= 0 && x < width && y < height && alpha[(y * width + x) as usize] >= 128
+    };
+
+    let sdf_width = width + spread * 2;
+    let sdf_height = height + spread * 2;
+    let mut sdf = Vec::with_capacity((sdf_width * sdf_height) as usize);
+    for sdf_y in 0..sdf_height {
+        for sdf_x in 0..sdf_width {
+            let x = sdf_x - spread;
+            let y = sdf_y - spread;
+            let inside = is_inside(x, y);
+
+            // Find the nearest pixel on the other side of the edge
+            let mut nearest_squared = (spread * spread) as f32;
+            for offset_y in -spread..spread + 1 {
+                for offset_x in -spread..spread + 1 {
+                    if is_inside(x + offset_x, y + offset_y) != inside {
+                        let distance_squared = (offset_x * offset_x + offset_y * offset_y) as f32;
+                        if distance_squared < nearest_squared {
+                            nearest_squared = distance_squared;
+                        }
+                    }
+                }
+            }
+
+            // The edge lies halfway between the pixel centers
+            let distance = (nearest_squared.sqrt() - 0.5).max(0.0);
+            let signed_distance = if inside { distance } else { -distance };
+            let value = 0.5 + signed_distance / (spread as f32 * 2.0);
+            sdf.push((value.max(0.0).min(1.0) * 255.0).round() as u8);
+        }
+    }
+    sdf
+}
@@ -0,0 +1,217 @@
+//! Rasterization of TrueType/OpenType fonts into a glyph atlas at runtime.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs::File;
+use std::io::Read;
+use std::path::PathBuf;
+
+use rusttype::{self, point, Scale};
+
+use super::atlas::{AtlasPacker, GLYPH_PADDING};
+use super::reload::{FontOrigin, FontSource};
+use super::{CharacterData, Font, FontError};
+
+/// Holds the TrueType font a [`Font`](struct.Font.html) was created from, so new glyphs can be rasterized into the atlas when needed.
+pub(crate) struct TrueTypeRasterizer {
+    font: rusttype::Font<'static>,
+    scale: Scale,
+    ascent: f32,
+    missing: HashSet<char>,
+}
+
+impl fmt::Debug for TrueTypeRasterizer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TrueTypeRasterizer")
+            .field("scale", &self.scale)
+            .field("ascent", &self.ascent)
+            .field("missing", &self.missing)
+            .finish()
+    }
+}
+
+impl PartialEq for TrueTypeRasterizer {
+    // The rasterized glyphs are compared through the atlas and characters of the Font itself.
+    fn eq(&self, other: &TrueTypeRasterizer) -> bool {
+        self.scale == other.scale && self.ascent == other.ascent
+    }
+}
+
+impl Font {
+    /// Loads a TrueType or OpenType font from the given path, rasterizing it at the given pixel size (line height). Example:
+    ///
+    /// ```
+    /// use glerminal::font::Font;
+    /// let font = Font::load_truetype("fonts/SourceCodePro-Medium.ttf", 24);
+    /// ```
+    ///
+    /// The printable ASCII characters are rasterized into the font's atlas immediately, other characters are rasterized
+    /// the first time they are flushed to the screen.
+    ///
+    /// Panics if the font could not be loaded, see [`try_load_truetype`](#method.try_load_truetype) for a non-panicking version.
+    pub fn load_truetype<T: Into<PathBuf>>(font_path: T, pixel_size: u32) -> Font {
+        match Font::try_load_truetype(font_path, pixel_size) {
+            Ok(font) => font,
+            Err(error) => panic!("Failed to load font: {}", error),
+        }
+    }
+
+    /// Loads a TrueType or OpenType font from the given bytes (ie. from `include_bytes!`), rasterizing it at the given pixel size.
+    ///
+    /// Panics if the font could not be loaded, see [`try_load_truetype_raw`](#method.try_load_truetype_raw) for a non-panicking version.
+    pub fn load_truetype_raw<T: Into<Vec<u8>>>(font_data: T, pixel_size: u32) -> Font {
+        match Font::try_load_truetype_raw(font_data, pixel_size) {
+            Ok(font) => font,
+            Err(error) => panic!("Failed to load font: {}", error),
+        }
+    }
+
+    /// Loads a TrueType or OpenType font from the given path, returning a [`FontError`](enum.FontError.html) if the loading fails.
+    pub fn try_load_truetype<T: Into<PathBuf>>(
+        font_path: T,
+        pixel_size: u32,
+    ) -> Result<Font, FontError> {
+        let font_path = font_path.into();
+        let mut font_data = Vec::new();
+        File::open(&font_path)?.read_to_end(&mut font_data)?;
+        let mut font = Font::try_load_truetype_raw(font_data, pixel_size)?;
+        if let Some(stem) = font_path.file_stem() {
+            font.name = stem.to_string_lossy().into_owned();
+        }
+        font.origin = FontOrigin(Some(FontSource::TrueType {
+            font_path,
+            pixel_size,
+        }));
+        Ok(font)
+    }
+
+    /// Loads a TrueType or OpenType font from the given bytes, returning a [`FontError`](enum.FontError.html) if the loading fails.
+    pub fn try_load_truetype_raw<T: Into<Vec<u8>>>(
+        font_data: T,
+        pixel_size: u32,
+    ) -> Result<Font, FontError> {
+        let tt_font = match rusttype::Font::from_bytes(font_data.into()) {
+            Ok(font) => font,
+            Err(error) => return Err(FontError::TrueType(error.to_string())),
+        };
+        if pixel_size == 0 {
+            return Err(FontError::TrueType("Pixel size must be above 0".to_owned()));
+        }
+
+        let scale = Scale::uniform(pixel_size as f32);
+        let v_metrics = tt_font.v_metrics(scale);
+        let line_height = (v_metrics.ascent - v_metrics.descent + v_metrics.line_gap).ceil() as u32;
+        let size = tt_font
+            .glyph('M')
+            .scaled(scale)
+            .h_metrics()
+            .advance_width
+            .ceil() as u32;
+
+        let width = (size.max(1) + GLYPH_PADDING) * 16;
+        let height = line_height.max(1) + GLYPH_PADDING;
+
+        let mut font = Font {
+            name: "TrueType".to_owned(),
+            image_buffer: vec![0; (width * height * 4) as usize],
+            width,
+            height,
+            line_height: line_height.max(1),
+            size: size.max(1),
+            min_offset_y: 0,
+            characters: HashMap::new(),
+            fallback_character: '?',
+            fallback_fonts: Vec::new(),
+            custom_glyphs: HashSet::new(),
+            truetype: Some(TrueTypeRasterizer {
+                font: tt_font,
+                scale,
+                ascent: v_metrics.ascent,
+                missing: HashSet::new(),
+            }),
+            packer: Some(AtlasPacker::new(0)),
+            atlas_version: 0,
+            origin: FontOrigin(None),
+            sdf_spread: None,
+        };
+
+        font.load_missing_characters((0x20u8..0x7F).map(|c| c as char));
+
+        Ok(font)
+    }
+
+    /// Rasterizes the given character into the atlas, and returns whether the Font has a glyph for it afterwards.
+    /// Does nothing if the Font was not loaded from a TrueType font.
+    pub(crate) fn rasterize_missing_character(&mut self, character: char) -> bool {
+        let mut rasterizer = match self.truetype.take() {
+            Some(rasterizer) => rasterizer,
+            None => return false,
+        };
+        if !rasterizer.missing.contains(&character) {
+            self.rasterize_character(&mut rasterizer, character);
+        }
+        self.truetype = Some(rasterizer);
+        self.characters.contains_key(&character)
+    }
+
+    fn rasterize_character(&mut self, rasterizer: &mut TrueTypeRasterizer, character: char) {
+        let glyph = rasterizer.font.glyph(character);
+        if glyph.id().0 == 0 {
+            // The character does not exist in the font, use the fallback character instead.
+            rasterizer.missing.insert(character);
+            return;
+        }
+        let glyph = glyph
+            .scaled(rasterizer.scale)
+            .positioned(point(0.0, rasterizer.ascent));
+
+        let bounding_box = match glyph.pixel_bounding_box() {
+            Some(bounding_box) => bounding_box,
+            None => {
+                // Glyphs without an outline (like whitespace) are empty
+                self.characters.insert(
+                    character,
+                    CharacterData {
+                        id: character as i32,
+                        x1: 0.0,
+                        x2: 0.0,
+                        y1: 0.0,
+                        y2: 0.0,
+                        width: 0,
+                        height: 0,
+                        x_off: 0,
+                        y_off: 0,
+                    },
+                );
+                return;
+            }
+        };
+        let glyph_width = bounding_box.width() as u32;
+        let glyph_height = bounding_box.height() as u32;
+
+        let mut alpha = vec![0; (glyph_width * glyph_height) as usize];
+        glyph.draw(|x, y, coverage| {
+            alpha[(y * glyph_width + x) as usize] = (coverage * 255.0).round() as u8;
+        });
+        let (atlas_x, atlas_y, region_width, region_height) =
+            self.pack_alpha_glyph(&alpha, glyph_width, glyph_height);
+        let padding = self.sdf_spread.unwrap_or(0) as i32;
+
+        let width_float = self.width as f32;
+        let height_float = self.height as f32;
+        self.characters.insert(
+            character,
+            CharacterData {
+                id: character as i32,
+                x1: atlas_x as f32 / width_float,
+                x2: (atlas_x + region_width) as f32 / width_float,
+                y1: atlas_y as f32 / height_float,
+                y2: (atlas_y + region_height) as f32 / height_float,
+                width: region_width as i32,
+                height: region_height as i32,
+                x_off: bounding_box.min.x - padding,
+                y_off: bounding_box.min.y - padding,
+            },
+        );
+    }
+}
@@ -65,7 +65,9 @@ extern crate glutin;
 extern crate libc;
 extern crate png;
 extern crate regex;
+extern crate rusttype;
 extern crate sfl_parser;
+extern crate unicode_width;
 
 #[cfg(test)]
 mod tests;
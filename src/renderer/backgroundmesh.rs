@@ -1,11 +1,11 @@
 use std::cell::Cell;
 
 use super::{Program, Renderable, Texture, Vao, Vbo};
-use text_buffer::TextBuffer;
+use text_buffer::{Color, TextAttributes, TextBuffer};
 
 pub struct BackgroundMesh {
-    width: i32,
-    height: i32,
+    width: Cell<i32>,
+    height: Cell<i32>,
     vbo_pos: Vbo,
     vbo_col: Vbo,
     vao: Vao,
@@ -65,8 +65,8 @@ impl BackgroundMesh {
         let count = width * height * 6;
 
         BackgroundMesh {
-            width: width,
-            height: height,
+            width: Cell::new(width),
+            height: Cell::new(height),
             vbo_pos: vbo_pos,
             vbo_col: vbo_col,
             vao: vao,
@@ -74,6 +74,16 @@ impl BackgroundMesh {
         }
     }
 
+    /// Reallocates the vertex buffers for the given dimensions; the mesh is empty until it's updated.
+    pub fn resize(&self, dimensions: (i32, i32)) {
+        let (width, height) = dimensions;
+        self.width.set(width);
+        self.height.set(height);
+        super::upload_buffer(self.vbo_pos, vec![0.0; (width * height * 12) as usize]);
+        super::upload_buffer(self.vbo_col, vec![0.0; (width * height * 24) as usize]);
+        self.count.set(0);
+    }
+
     pub fn update(&self, text_buffer: &TextBuffer) {
         if (text_buffer.height * text_buffer.width) as usize != text_buffer.chars.len() {
             panic!("Given TextBuffer height/width do not math chars.len()");
@@ -85,38 +95,54 @@ impl BackgroundMesh {
         // Create new color vertex buffer
         let mut vertex_buffer_col: Vec<f32> = Vec::new();
 
-        let character_width = 1.0 / self.width as f32;
-        let character_height = 1.0 / self.height as f32;
+        let character_width = 1.0 / self.width.get() as f32;
+        let character_height = 1.0 / self.height.get() as f32;
+        let line_thickness = character_height / 12.0;
         for y in 0..text_buffer.height {
             for x in 0..text_buffer.width {
-                let character = text_buffer.get_character(x, y);
+                let character = text_buffer.get_visible_character(x, y);
+                let attributes = character.get_attributes();
+                let (fg_color, bg_color) = character.get_drawn_colors();
 
-                if character.get_bg_color() == [0.0; 4] {
-                    continue;
-                }
-
-                // New Vertex Buffers
                 let x_off = x as f32 * character_width;
                 let y_off = y as f32 * character_height;
-                let mut single_character_vbuff = vec![
-                    x_off,
-                    y_off + character_height,
-                    x_off + character_width,
-                    y_off + character_height,
-                    x_off,
-                    y_off,
-                    x_off + character_width,
-                    y_off,
-                    x_off,
-                    y_off,
-                    x_off + character_width,
-                    y_off + character_height,
-                ];
-                vertex_buffer_pos.append(&mut single_character_vbuff);
 
-                // Get colors
-                for _ in 0..6 {
-                    vertex_buffer_col.append(&mut character.get_bg_color().to_vec());
+                // Background, and the underline and strikethrough drawn over it
+                let mut quads = Vec::new();
+                if bg_color != Color::TRANSPARENT {
+                    quads.push((y_off, character_height, bg_color));
+                }
+                if attributes.contains(TextAttributes::UNDERLINE) {
+                    let line_y = y_off + character_height - line_thickness * 2.0;
+                    quads.push((line_y, line_thickness, fg_color));
+                }
+                if attributes.contains(TextAttributes::STRIKETHROUGH) {
+                    let line_y = y_off + (character_height - line_thickness) / 2.0;
+                    quads.push((line_y, line_thickness, fg_color));
+                }
+
+                for (y_off, height, color) in quads {
+                    // New Vertex Buffers
+                    let mut single_character_vbuff = vec![
+                        x_off,
+                        y_off + height,
+                        x_off + character_width,
+                        y_off + height,
+                        x_off,
+                        y_off,
+                        x_off + character_width,
+                        y_off,
+                        x_off,
+                        y_off,
+                        x_off + character_width,
+                        y_off + height,
+                    ];
+                    vertex_buffer_pos.append(&mut single_character_vbuff);
+
+                    // Get colors
+                    for _ in 0..6 {
+                        vertex_buffer_col.append(&mut color.to_array().to_vec());
+                    }
                 }
             }
         }
@@ -0,0 +1,91 @@
+use std::cell::Cell;
+
+use super::{Program, Renderable, Texture, Vao, Vbo};
+use text_buffer::TextBuffer;
+
+/// The quad the cursor of a `TextBuffer` is drawn with.
+pub struct CursorMesh {
+    vbo_pos: Vbo,
+    vbo_col: Vbo,
+    vbo_style: Vbo,
+    vao: Vao,
+    count: Cell<i32>,
+}
+
+impl Renderable for CursorMesh {
+    fn get_vao(&self) -> Vao {
+        self.vao
+    }
+
+    fn get_count(&self) -> i32 {
+        self.count.get()
+    }
+
+    fn get_texture(&self) -> Option<Texture> {
+        None
+    }
+}
+
+impl CursorMesh {
+    pub fn new(program: Program) -> CursorMesh {
+        let vbo_pos = super::create_vbo(vec![0.0; 12]);
+        let vbo_col = super::create_vbo(vec![0.0; 24]);
+        let vbo_tex = super::create_vbo(vec![0.0; 12]);
+        let vbo_shakiness = super::create_vbo(vec![0.0; 6]);
+        let vbo_style = super::create_vbo(vec![0.0; 12]);
+        let vao = super::create_vao(
+            program,
+            vbo_pos,
+            vbo_col,
+            Some((vbo_tex, vbo_shakiness, vbo_style)),
+        );
+
+        CursorMesh {
+            vbo_pos,
+            vbo_col,
+            vbo_style,
+            vao,
+            count: Cell::new(0),
+        }
+    }
+
+    pub fn update(&self, text_buffer: &TextBuffer) {
+        let (x_off, y_off, width, height) = match text_buffer.get_cursor_rect() {
+            Some(rect) => rect,
+            None => {
+                self.count.set(0);
+                return;
+            }
+        };
+
+        let vertex_buffer_pos = vec![
+            x_off,
+            y_off + height,
+            x_off + width,
+            y_off + height,
+            x_off,
+            y_off,
+            x_off + width,
+            y_off,
+            x_off,
+            y_off,
+            x_off + width,
+            y_off + height,
+        ];
+
+        let mut vertex_buffer_col = Vec::new();
+        let mut vertex_buffer_style = Vec::new();
+        for _ in 0..6 {
+            vertex_buffer_col.append(&mut text_buffer.get_cursor_color().to_array().to_vec());
+            // No italic skew, and the blinks per second
+            vertex_buffer_style.push(0.0);
+            vertex_buffer_style.push(text_buffer.get_cursor_blink_rate());
+        }
+
+        self.count.set(6);
+
+        super::upload_buffer(self.vbo_pos, vertex_buffer_pos);
+        super::upload_buffer(self.vbo_col, vertex_buffer_col);
+        super::upload_buffer(self.vbo_style, vertex_buffer_style);
+    }
+}
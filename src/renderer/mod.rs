@@ -1,5 +1,6 @@
 pub(crate) mod textbuffermesh;
 pub(crate) mod backgroundmesh;
+pub(crate) mod cursormesh;
 
 use gl;
 use std::mem;
@@ -10,6 +11,7 @@ use std::ffi::{CStr, CString};
 
 pub(crate) static VERT_SHADER: &'static str = include_str!("../shaders/vert_shader.glsl");
 pub(crate) static FRAG_SHADER: &'static str = include_str!("../shaders/frag_shader.glsl");
+pub(crate) static SDF_FRAG_SHADER: &'static str = include_str!("../shaders/sdf_frag_shader.glsl");
 pub(crate) static BG_FRAG_SHADER: &'static str = include_str!("../shaders/bg_frag_shader.glsl");
 pub(crate) static DEBUG_FRAG_SHADER: &'static str =
     include_str!("../shaders/debug_frag_shader.glsl");
@@ -124,6 +126,26 @@ pub(crate) fn create_proj_matrix(dimensions: (f32, f32), aspect_ratio: f32) -> M
     let right = 1.0 + overflow_width / 2 as f32;
     let bottom = 1.0 + overflow_height / 2 as f32;
 
+    create_ortho_matrix(left, right, top, bottom)
+}
+
+/// Creates a projection matrix that draws the (0, 0) - (1, 1) area at exactly the given content size in pixels,
+/// centered in the window and aligned to whole pixels.
+pub(crate) fn create_pixel_proj_matrix(dimensions: (u32, u32), content: (u32, u32)) -> Matrix4 {
+    let (width, height) = (dimensions.0 as f32, dimensions.1 as f32);
+    let (content_width, content_height) = (content.0.max(1) as f32, content.1.max(1) as f32);
+    let offset_x = (dimensions.0 as i32 - content.0 as i32) / 2;
+    let offset_y = (dimensions.1 as i32 - content.1 as i32) / 2;
+
+    let left = -offset_x as f32 / content_width;
+    let top = -offset_y as f32 / content_height;
+    let right = left + width / content_width;
+    let bottom = top + height / content_height;
+
+    create_ortho_matrix(left, right, top, bottom)
+}
+
+fn create_ortho_matrix(left: f32, right: f32, top: f32, bottom: f32) -> Matrix4 {
     let far = 1.0;
     let near = -1.0;
     [
@@ -146,7 +168,7 @@ pub(crate) fn create_proj_matrix(dimensions: (f32, f32), aspect_ratio: f32) -> M
     ]
 }
 
-pub(crate) fn create_texture(pixels: &[u8], width: u32, height: u32) -> Texture {
+pub(crate) fn create_texture(pixels: &[u8], width: u32, height: u32, smooth: bool) -> Texture {
     unsafe {
         let mut tex = 0;
         gl::GenTextures(1, &mut tex);
@@ -156,7 +178,27 @@ pub(crate) fn create_texture(pixels: &[u8], width: u32, height: u32) -> Texture
         gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
         gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
         gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
+
+        upload_texture(tex, pixels, width, height, smooth);
+
+        tex
+    }
+}
+
+/// Uploads the pixels into the texture. If `smooth` is true, the texture is magnified with linear filtering (ie. for SDF fonts),
+/// otherwise with nearest filtering.
+pub(crate) fn upload_texture(
+    texture: Texture,
+    pixels: &[u8],
+    width: u32,
+    height: u32,
+    smooth: bool,
+) {
+    unsafe {
+        gl::BindTexture(gl::TEXTURE_2D, texture);
+
+        let mag_filter = if smooth { gl::LINEAR } else { gl::NEAREST };
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, mag_filter as i32);
 
         let data_pointer = pixels.as_ptr() as *const c_void;
         gl::TexImage2D(
@@ -170,8 +212,6 @@ pub(crate) fn create_texture(pixels: &[u8], width: u32, height: u32) -> Texture
             gl::UNSIGNED_BYTE,
             data_pointer,
         );
-
-        tex
     }
 }
 
@@ -203,7 +243,7 @@ pub(crate) fn create_vao(
     program: Program,
     vbo_pos: Vbo,
     vbo_col: Vbo,
-    vbo_tuple: Option<(Vbo, Vbo)>,
+    vbo_tuple: Option<(Vbo, Vbo, Vbo)>,
 ) -> Vao {
     unsafe {
         let mut vao = 0;
@@ -212,29 +252,13 @@ pub(crate) fn create_vao(
 
         gl::BindVertexArray(vao);
 
-        let attrib_location = get_attrib_location(program, "position");
-        gl::EnableVertexAttribArray(attrib_location);
-        gl::BindBuffer(gl::ARRAY_BUFFER, vbo_pos);
-        gl::VertexAttribPointer(attrib_location, 2, gl::FLOAT, gl::FALSE, 0, ptr::null());
-
-        let attrib_location = get_attrib_location(program, "color");
-
-        gl::EnableVertexAttribArray(attrib_location);
-        gl::BindBuffer(gl::ARRAY_BUFFER, vbo_col);
-        gl::VertexAttribPointer(attrib_location, 4, gl::FLOAT, gl::FALSE, 0, ptr::null());
-
-        if let Some((vbo_tex, vbo_shakiness)) = vbo_tuple {
-            let attrib_location = get_attrib_location(program, "texcoord");
-
-            gl::EnableVertexAttribArray(attrib_location);
-            gl::BindBuffer(gl::ARRAY_BUFFER, vbo_tex);
-            gl::VertexAttribPointer(attrib_location, 2, gl::FLOAT, gl::FALSE, 0, ptr::null());
+        bind_attribute(program, "position", vbo_pos, 2);
+        bind_attribute(program, "color", vbo_col, 4);
 
-            let attrib_location = get_attrib_location(program, "shakiness");
-
-            gl::EnableVertexAttribArray(attrib_location);
-            gl::BindBuffer(gl::ARRAY_BUFFER, vbo_shakiness);
-            gl::VertexAttribPointer(attrib_location, 1, gl::FLOAT, gl::FALSE, 0, ptr::null());
+        if let Some((vbo_tex, vbo_shakiness, vbo_style)) = vbo_tuple {
+            bind_attribute(program, "texcoord", vbo_tex, 2);
+            bind_attribute(program, "shakiness", vbo_shakiness, 1);
+            bind_attribute(program, "style", vbo_style, 2);
         }
 
         vao
@@ -291,11 +315,29 @@ fn create_shader(shader_text: &str, shader_type: u32) -> u32 {
     }
 }
 
-unsafe fn get_attrib_location(program: Program, attribute: &str) -> u32 {
-    gl::GetAttribLocation(
+/// Binds the vbo to the attribute of the program in the currently bound vao, with the given amount of floats per vertex.
+/// Attributes the program doesn't use (ie. the texcoords in the background program) are skipped.
+unsafe fn bind_attribute(program: Program, attribute: &str, vbo: Vbo, size: i32) {
+    let attrib_location = match get_attrib_location(program, attribute) {
+        Some(location) => location,
+        None => return,
+    };
+    gl::EnableVertexAttribArray(attrib_location);
+    gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+    gl::VertexAttribPointer(attrib_location, size, gl::FLOAT, gl::FALSE, 0, ptr::null());
+}
+
+/// Returns the location of the attribute, or None if the program has no active attribute with that name.
+unsafe fn get_attrib_location(program: Program, attribute: &str) -> Option<u32> {
+    let location = gl::GetAttribLocation(
         program,
         CString::new(attribute).unwrap().as_ptr() as *const i8,
-    ) as u32
+    );
+    if location < 0 {
+        None
+    } else {
+        Some(location as u32)
+    }
 }
 
 unsafe fn get_uniform_location(uniform: String, program: Program) -> i32 {
@@ -2,7 +2,7 @@ use std::cell::Cell;
 
 use super::{Program, Renderable, Texture, Vao, Vbo};
 use font::Font;
-use text_buffer::TextBuffer;
+use text_buffer::{TextAttributes, TextBuffer};
 
 pub struct TextBufferMesh {
     vao: Vao,
@@ -10,8 +10,10 @@ pub struct TextBufferMesh {
     vbo_col: Vbo,
     vbo_tex: Vbo,
     vbo_shakiness: Vbo,
+    vbo_style: Vbo,
     count: Cell<i32>,
     texture: Texture,
+    atlas_version: Cell<u32>,
 }
 
 impl Renderable for TextBufferMesh {
@@ -36,30 +38,67 @@ impl TextBufferMesh {
         let vertex_buffer_col = vec![1.0; (width * height * 24) as usize];
         let vertex_buffer_tex = vec![0.0; (width * height * 12) as usize];
         let vertex_buffer_shakiness = vec![0.0; (width * height * 6) as usize];
+        let vertex_buffer_style = vec![0.0; (width * height * 12) as usize];
 
         let vbo_pos = super::create_vbo(vertex_buffer_pos);
         let vbo_col = super::create_vbo(vertex_buffer_col);
         let vbo_tex = super::create_vbo(vertex_buffer_tex);
         let vbo_shakiness = super::create_vbo(vertex_buffer_shakiness);
-        let vao = super::create_vao(program, vbo_pos, vbo_col, Some((vbo_tex, vbo_shakiness)));
-
-        let tex = super::create_texture(&font.image_buffer, font.width, font.height);
+        let vbo_style = super::create_vbo(vertex_buffer_style);
+        let vao = super::create_vao(
+            program,
+            vbo_pos,
+            vbo_col,
+            Some((vbo_tex, vbo_shakiness, vbo_style)),
+        );
+
+        let tex = super::create_texture(&font.image_buffer, font.width, font.height, font.is_sdf());
         TextBufferMesh {
             vao: vao,
             vbo_pos: vbo_pos,
             vbo_col: vbo_col,
             vbo_tex: vbo_tex,
             vbo_shakiness: vbo_shakiness,
+            vbo_style: vbo_style,
             count: Cell::new(width * height * 6),
             texture: tex,
+            atlas_version: Cell::new(font.atlas_version),
         }
     }
 
+    /// Reallocates the vertex buffers for the given dimensions; the mesh is empty until it's updated.
+    pub fn resize(&self, dimensions: (i32, i32)) {
+        let (width, height) = dimensions;
+        super::upload_buffer(self.vbo_pos, vec![0.0; (width * height * 12) as usize]);
+        super::upload_buffer(self.vbo_col, vec![1.0; (width * height * 24) as usize]);
+        super::upload_buffer(self.vbo_tex, vec![0.0; (width * height * 12) as usize]);
+        super::upload_buffer(self.vbo_shakiness, vec![0.0; (width * height * 6) as usize]);
+        super::upload_buffer(self.vbo_style, vec![0.0; (width * height * 12) as usize]);
+        self.count.set(0);
+    }
+
+    /// Returns whether the font has been replaced or its atlas changed since the mesh was last updated
+    pub fn is_outdated(&self, font: &Font) -> bool {
+        self.atlas_version.get() != font.atlas_version
+    }
+
     pub fn update(&self, text_buffer: &TextBuffer, font: &Font) {
         if (text_buffer.height * text_buffer.width) as usize != text_buffer.chars.len() {
             panic!("Given TextBuffer height/width do not math chars.len()");
         }
 
+        // Re-upload the font atlas if glyphs have been added to it since
+        if self.is_outdated(font) {
+            super::upload_texture(
+                self.texture,
+                &font.image_buffer,
+                font.width,
+                font.height,
+                font.is_sdf(),
+            );
+            self.atlas_version.set(font.atlas_version);
+        }
+
         // Create new position veretex buffer
         let mut vertex_buffer_pos: Vec<f32> = Vec::new();
 
@@ -72,69 +111,112 @@ impl TextBufferMesh {
         // Create new tex coords
         let mut vertex_buffer_shakiness: Vec<f32> = Vec::new();
 
+        // Create new styles (italic skew and blinks per second)
+        let mut vertex_buffer_style: Vec<f32> = Vec::new();
+
         // Fill those arrays
         let character_width = 1.0 / text_buffer.width as f32;
         let character_height = 1.0 / text_buffer.height as f32;
         for y in 0..text_buffer.height {
             for x in 0..text_buffer.width {
                 // Calculate pos vertex coords
-                let character = text_buffer.get_character(x, y);
-                if character.get_char() == ' ' {
-                    continue;
+                let character = text_buffer.get_visible_character(x, y);
+                let attributes = character.get_attributes();
+                let (fg_color, _) = character.get_drawn_colors();
+                // Italic characters are skewed in the shader by their height from the bottom of the cell
+                let skew = if attributes.contains(TextAttributes::ITALIC) {
+                    character_width / character_height
+                } else {
+                    0.0
+                };
+                let cell_bottom = (y + 1) as f32 * character_height;
+                let blink = if attributes.contains(TextAttributes::BLINK) {
+                    1.0
+                } else {
+                    0.0
+                };
+                // Bold characters are drawn twice, the second time one font pixel to the right
+                let copies = if attributes.contains(TextAttributes::BOLD) {
+                    2
+                } else {
+                    1
+                };
+
+                // The char and the combining characters attached to it are drawn over each other
+                for glyph in character.get_grapheme().chars() {
+                    if glyph == ' ' {
+                        continue;
+                    }
+                    let char_data = match font.get_character_or_fallback(glyph) {
+                        Some(char_data) => char_data,
+                        None => continue,
+                    };
+                    let width = character_width * (char_data.width as f32 / font.size as f32);
+                    let height =
+                        character_height * (char_data.height as f32 / font.line_height as f32);
+
+                    let font_offset = -(font.min_offset_y as i32);
+
+                    let bmoffset_x =
+                        character_width * (char_data.x_off as i32 as f32 / font.size as f32);
+                    let bmoffset_y = character_height
+                        * ((char_data.y_off as i32 + font_offset) as f32 / font.line_height as f32);
+
+                    // Wide characters are centered on the two cells they take
+                    let x_off = if character.get_width() == 2 {
+                        x as f32 * character_width + (character_width * 2.0 - width) / 2.0
+                    } else {
+                        x as f32 * character_width + bmoffset_x
+                    };
+                    let y_off = y as f32 * character_height + bmoffset_y;
+
+                    for copy in 0..copies {
+                        let x_off = x_off + copy as f32 * character_width / font.size as f32;
+                        let mut single_character_vbuff = vec![
+                            x_off,
+                            y_off + height,
+                            x_off + width,
+                            y_off + height,
+                            x_off,
+                            y_off,
+                            x_off + width,
+                            y_off,
+                            x_off,
+                            y_off,
+                            x_off + width,
+                            y_off + height,
+                        ];
+                        for vertex in single_character_vbuff.chunks(2) {
+                            vertex_buffer_style.push((cell_bottom - vertex[1]) * skew);
+                            vertex_buffer_style.push(blink);
+                        }
+                        vertex_buffer_pos.append(&mut single_character_vbuff);
+
+                        // Color and Shakiness
+                        for _ in 0..6 {
+                            vertex_buffer_col.append(&mut fg_color.to_array().to_vec());
+                            vertex_buffer_shakiness.push(character.get_shakiness());
+                        }
+
+                        // Calculate tex coords
+                        let mut char_tex_coords = vec![
+                            char_data.x1,
+                            char_data.y2,
+                            char_data.x2,
+                            char_data.y2,
+                            char_data.x1,
+                            char_data.y1,
+                            char_data.x2,
+                            char_data.y1,
+                            char_data.x1,
+                            char_data.y1,
+                            char_data.x2,
+                            char_data.y2,
+                        ];
+
+                        vertex_buffer_tex.append(&mut char_tex_coords);
+                    }
                 }
-                let char_data = font.get_character(character.get_char())
-                    .unwrap_or(font.get_character('?').unwrap());
-                let width = character_width * (char_data.width as f32 / font.size as f32);
-                let height = character_height * (char_data.height as f32 / font.line_height as f32);
-
-                let font_offset = -(font.min_offset_y as i32);
-
-                let bmoffset_x =
-                    character_width * (char_data.x_off as i32 as f32 / font.size as f32);
-                let bmoffset_y = character_height
-                    * ((char_data.y_off as i32 + font_offset) as f32 / font.line_height as f32);
-
-                let x_off = x as f32 * character_width + bmoffset_x;
-                let y_off = y as f32 * character_height + bmoffset_y;
-                let mut single_character_vbuff = vec![
-                    x_off,
-                    y_off + height,
-                    x_off + width,
-                    y_off + height,
-                    x_off,
-                    y_off,
-                    x_off + width,
-                    y_off,
-                    x_off,
-                    y_off,
-                    x_off + width,
-                    y_off + height,
-                ];
-                vertex_buffer_pos.append(&mut single_character_vbuff);
-
-                // Color and Shakiness
-                for _ in 0..6 {
-                    vertex_buffer_col.append(&mut character.get_fg_color().to_vec());
-                    vertex_buffer_shakiness.push(character.get_shakiness());
-                }
-
-                // Calculate tex coords
-                let mut char_tex_coords = vec![
-                    char_data.x1,
-                    char_data.y2,
-                    char_data.x2,
-                    char_data.y2,
-                    char_data.x1,
-                    char_data.y1,
-                    char_data.x2,
-                    char_data.y1,
-                    char_data.x1,
-                    char_data.y1,
-                    char_data.x2,
-                    char_data.y2,
-                ];
-
-                vertex_buffer_tex.append(&mut char_tex_coords);
             }
         }
 
@@ -144,5 +226,6 @@ impl TextBufferMesh {
         super::upload_buffer(self.vbo_col, vertex_buffer_col);
         super::upload_buffer(self.vbo_tex, vertex_buffer_tex);
         super::upload_buffer(self.vbo_shakiness, vertex_buffer_shakiness);
+        super::upload_buffer(self.vbo_style, vertex_buffer_style);
     }
 }
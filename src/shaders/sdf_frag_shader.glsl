@@ -0,0 +1,16 @@
+#version 330 core
+
+in vec2 f_texcoord;
+in vec4 f_color;
+
+out vec4 color;
+
+uniform sampler2D tex;
+
+void main() {
+  vec4 texel = texture(tex, f_texcoord);
+  float distance = texel.a;
+  float smoothing = max(fwidth(distance) * 0.5, 0.001);
+  float alpha = smoothstep(0.5 - smoothing, 0.5 + smoothing, distance);
+  color = vec4(texel.rgb, alpha) * f_color;
+}
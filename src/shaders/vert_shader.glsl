@@ -4,6 +4,8 @@ in vec2 position;
 in vec2 texcoord;
 in vec4 color;
 in float shakiness;
+// x: how far the vertex moves right when italic, y: how many times per second the vertex blinks
+in vec2 style;
 
 out vec2 f_texcoord;
 out vec4 f_color;
@@ -14,7 +16,9 @@ uniform float time;
 void main() {
   float x_shake = sin(time * sqrt(shakiness) * 50) * 0.02 * shakiness / 10;
   float y_shake = sin(time * sqrt(shakiness) * 40) * 0.03 * shakiness / 10;
-  gl_Position = proj_mat * vec4(position + vec2(x_shake, y_shake), 0, 1);
+  float italic_skew = style.x * 0.2;
+  gl_Position = proj_mat * vec4(position + vec2(x_shake + italic_skew, y_shake), 0, 1);
   f_texcoord = texcoord;
   f_color = color;
+  f_color.a *= 1 - step(0.5, fract(time * style.y));
 }
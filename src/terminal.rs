@@ -32,14 +32,57 @@
 //! terminal.set_title("Changed title!");
 //! terminal.show();
 //! ```
+//!
+//! ### Multiple fonts
+//! The font given to the `TerminalBuilder` is the default font, used by `TextBuffer::new`. Other fonts can be registered with
+//! [`add_font`](struct.Terminal.html#method.add_font), and the returned [`FontHandle`](struct.FontHandle.html)
+//! used to create `TextBuffer`s that use them, ie. to draw a HUD with a small font over a map drawn with a large tileset:
+//! ```no_run
+//! use glerminal::terminal::TerminalBuilder;
+//! use glerminal::text_buffer::TextBuffer;
+//! use glerminal::font::{Font, GridMapping};
+//!
+//! let terminal = TerminalBuilder::new()
+//!     .with_font(Font::load("fonts/iosevka.sfl"))
+//!     .build();
+//! let tileset = terminal.add_font(Font::from_grid("fonts/iosevka.png", (24, 23), GridMapping::Cp437).unwrap());
+//!
+//! let map = TextBuffer::new_with_font(&terminal, (40, 20), tileset).unwrap();
+//! let hud = TextBuffer::new(&terminal, (80, 40)).unwrap();
+//!
+//! terminal.draw_multiple(vec![&map, &hud]);
+//! ```
+//!
+//! ### Fitting the grid to the window
+//! Instead of letterboxing or stretching a `TextBuffer` to the window, it can be resized to as many cells as fit
+//! into the window, so that each cell is drawn at exactly the size of the font (times the
+//! [cell scale](struct.TerminalBuilder.html#method.with_cell_scale)). See
+//! [`TextBuffer::set_fit_to_window`](../text_buffer/struct.TextBuffer.html#method.set_fit_to_window):
+//! ```no_run
+//! use glerminal::terminal::{FontHandle, TerminalBuilder};
+//! use glerminal::text_buffer::TextBuffer;
+//!
+//! let terminal = TerminalBuilder::new().with_cell_scale(2).build();
+//! let grid_size = terminal.get_grid_size(FontHandle::default());
+//! let mut text_buffer = TextBuffer::new(&terminal, grid_size).unwrap();
+//! text_buffer.set_fit_to_window(true);
+//!
+//! while terminal.refresh() {
+//!     if terminal.was_resized() {
+//!         println!("The window now fits {:?} cells", terminal.get_grid_size(FontHandle::default()));
+//!     }
+//!     terminal.flush(&mut text_buffer);
+//!     terminal.draw(&text_buffer);
+//! }
+//! ```
 
 #[allow(unused_imports)]
 use glutin::VirtualKeyCode;
-use std::cell::{Cell, RefCell};
+use std::cell::{Cell, Ref, RefCell, RefMut};
 use std::time::{Duration, SystemTime};
 
 use display::Display;
-use font::Font;
+use font::{Font, FontError, FontWatcher};
 use input::Input;
 use renderer;
 use text_buffer::TextBuffer;
@@ -58,6 +101,8 @@ pub struct TerminalBuilder {
     visibility: bool,
     headless: bool,
     text_buffer_aspect_ratio: bool,
+    font_hot_reload: bool,
+    cell_scale: u32,
 }
 
 #[allow(dead_code)]
@@ -72,6 +117,8 @@ impl TerminalBuilder {
             visibility: true,
             headless: false,
             text_buffer_aspect_ratio: true,
+            font_hot_reload: false,
+            cell_scale: 1,
         }
     }
 
@@ -123,6 +170,41 @@ impl TerminalBuilder {
         self
     }
 
+    /// Changes whether the font is reloaded when its files are modified (default false).
+    ///
+    /// When enabled, the files the font was loaded from (ie. the .sfl and .png passed to `Font::load`) are checked
+    /// for changes on every `refresh`, and if they have changed, the font is reloaded and the `TextBuffer`s are
+    /// rebuilt with it the next time they're flushed. If the reloading fails, the old font is kept and the error can be
+    /// retrieved with [`take_font_reload_error`](struct.Terminal.html#method.take_font_reload_error).
+    ///
+    /// Only fonts loaded from paths can be reloaded; fonts loaded with the `_raw` methods are never reloaded.
+    /// Fallback fonts and custom glyphs added to the font are kept when it's reloaded (the fallback fonts themselves
+    /// are not reloaded), and SDF fonts are converted again. Example:
+    ///
+    /// ```no_run
+    /// use glerminal::terminal::TerminalBuilder;
+    /// use glerminal::font::Font;
+    ///
+    /// let terminal = TerminalBuilder::new()
+    ///     .with_font(Font::load("fonts/iosevka.sfl"))
+    ///     .with_font_hot_reload(cfg!(debug_assertions))
+    ///     .build();
+    /// ```
+    pub fn with_font_hot_reload(mut self, font_hot_reload: bool) -> TerminalBuilder {
+        self.font_hot_reload = font_hot_reload;
+        self
+    }
+
+    /// Changes the integer scale at which the cells of `TextBuffer`s that fit the window are drawn (default 1).
+    ///
+    /// With a scale of 2, a cell of a 16 pixel font takes 32 pixels of the window. See
+    /// [`TextBuffer::set_fit_to_window`](../text_buffer/struct.TextBuffer.html#method.set_fit_to_window).
+    /// Scales below 1 are treated as 1.
+    pub fn with_cell_scale(mut self, cell_scale: u32) -> TerminalBuilder {
+        self.cell_scale = cell_scale.max(1);
+        self
+    }
+
     /// Builds the actual terminal and opens the window
     pub fn build(self) -> Terminal {
         Terminal::new(
@@ -133,25 +215,47 @@ impl TerminalBuilder {
             self.visibility,
             self.headless,
             self.text_buffer_aspect_ratio,
+            self.font_hot_reload,
+            self.cell_scale,
         )
     }
 }
 
+/// A handle to a font registered with a `Terminal`, see [`Terminal::add_font`](struct.Terminal.html#method.add_font).
+///
+/// Handles are only valid for the `Terminal` that created them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FontHandle(usize);
+
+impl Default for FontHandle {
+    /// Returns the handle of the default font, which is the font given to the `TerminalBuilder`.
+    fn default() -> FontHandle {
+        FontHandle(0)
+    }
+}
+
 /// Represents the Terminal itself.
 ///
 /// See [terminal mod](index.html) for examples and more detailed documentation.
 pub struct Terminal {
     display: Option<Display>,
     program: renderer::Program,
+    sdf_program: renderer::Program,
     background_program: renderer::Program,
     debug_program: renderer::Program,
     debug: Cell<bool>,
     running: Cell<bool>,
     pub(crate) headless: bool,
     since_start: SystemTime,
-    pub(crate) font: Font,
+    fonts: RefCell<Vec<Font>>,
     frame_counter: RefCell<FrameCounter>,
     text_buffer_aspect_ratio: bool,
+    font_hot_reload: bool,
+    font_watchers: RefCell<Vec<Option<FontWatcher>>>,
+    font_reload_error: RefCell<Option<FontError>>,
+    window_dimensions: Cell<(u32, u32)>,
+    resized: Cell<bool>,
+    cell_scale: Cell<u32>,
 }
 
 impl Terminal {
@@ -163,14 +267,18 @@ impl Terminal {
         visibility: bool,
         headless: bool,
         text_buffer_aspect_ratio: bool,
+        font_hot_reload: bool,
+        cell_scale: u32,
     ) -> Terminal {
         let display;
         let program;
+        let sdf_program;
         let background_program;
         let debug_program;
         if headless {
             display = None;
             program = 0;
+            sdf_program = 0;
             background_program = 0;
             debug_program = 0;
         } else {
@@ -181,24 +289,37 @@ impl Terminal {
                 visibility,
             ));
             program = renderer::create_program(renderer::VERT_SHADER, renderer::FRAG_SHADER);
+            sdf_program =
+                renderer::create_program(renderer::VERT_SHADER, renderer::SDF_FRAG_SHADER);
             background_program =
                 renderer::create_program(renderer::VERT_SHADER, renderer::BG_FRAG_SHADER);
             debug_program =
                 renderer::create_program(renderer::VERT_SHADER, renderer::DEBUG_FRAG_SHADER);
         }
-        let font = font;
+        let font_watcher = if font_hot_reload {
+            FontWatcher::new(&font)
+        } else {
+            None
+        };
         Terminal {
             display,
             program,
+            sdf_program,
             background_program,
             debug_program,
             debug: Cell::new(false),
             running: Cell::new(true),
             headless,
             since_start: SystemTime::now(),
-            font,
+            fonts: RefCell::new(vec![font]),
             frame_counter: RefCell::new(FrameCounter::new()),
             text_buffer_aspect_ratio,
+            font_hot_reload,
+            font_watchers: RefCell::new(vec![font_watcher]),
+            font_reload_error: RefCell::new(None),
+            window_dimensions: Cell::new(window_dimensions),
+            resized: Cell::new(false),
+            cell_scale: Cell::new(cell_scale),
         }
     }
 
@@ -216,14 +337,18 @@ impl Terminal {
         let mut frame_counter = self.frame_counter.borrow_mut();
         frame_counter.update();
         drop(frame_counter);
+        self.reload_fonts_if_modified();
 
         if let Some(ref display) = self.display {
             let input = self.get_current_input();
             if input.was_just_pressed(VirtualKeyCode::F3) {
                 self.set_debug(!self.debug.get());
             }
-            display.refresh() && self.running.get()
+            let running = display.refresh();
+            self.update_window_dimensions(display);
+            running && self.running.get()
         } else {
+            self.resized.set(false);
             self.running.get()
         }
     }
@@ -234,19 +359,120 @@ impl Terminal {
         let mut frame_counter = self.frame_counter.borrow_mut();
         frame_counter.update();
         drop(frame_counter);
+        self.reload_fonts_if_modified();
 
         if let Some(ref display) = self.display {
-            display.refresh() && self.running.get()
+            let running = display.refresh();
+            self.update_window_dimensions(display);
+            running && self.running.get()
         } else {
+            self.resized.set(false);
             self.running.get()
         }
     }
 
+    fn update_window_dimensions(&self, display: &Display) {
+        self.resized.set(display.was_resized());
+        self.window_dimensions.set(display.get_dimensions());
+    }
+
+    /// Returns the inner dimensions of the window in pixels (width, height).
+    ///
+    /// For a headless terminal, these are the dimensions given to the `TerminalBuilder`.
+    pub fn get_window_dimensions(&self) -> (u32, u32) {
+        self.window_dimensions.get()
+    }
+
+    /// Returns whether the window was resized during the last `refresh`.
+    pub fn was_resized(&self) -> bool {
+        self.resized.get()
+    }
+
+    /// Returns how many cells (width, height) of the font with the given handle fit into the window at the current
+    /// [cell scale](struct.Terminal.html#method.set_cell_scale). Both are always at least 1.
+    pub fn get_grid_size(&self, font_handle: FontHandle) -> (i32, i32) {
+        let (cell_width, cell_height) = self.get_cell_size(font_handle);
+        let (width, height) = self.window_dimensions.get();
+        (
+            (width / cell_width).max(1) as i32,
+            (height / cell_height).max(1) as i32,
+        )
+    }
+
+    /// Sets the integer scale at which the cells of `TextBuffer`s that fit the window are drawn.
+    /// Scales below 1 are treated as 1. See
+    /// [`TerminalBuilder::with_cell_scale`](struct.TerminalBuilder.html#method.with_cell_scale).
+    pub fn set_cell_scale(&self, cell_scale: u32) {
+        self.cell_scale.set(cell_scale.max(1));
+    }
+
+    /// Returns the integer scale at which the cells of `TextBuffer`s that fit the window are drawn.
+    pub fn get_cell_scale(&self) -> u32 {
+        self.cell_scale.get()
+    }
+
+    /// Returns the size of a cell of the font in pixels at the current cell scale.
+    fn get_cell_size(&self, font_handle: FontHandle) -> (u32, u32) {
+        let font = self.get_font_by_handle(font_handle);
+        let scale = self.cell_scale.get();
+        (
+            (font.size * scale).max(1),
+            (font.line_height * scale).max(1),
+        )
+    }
+
+    /// Returns the error that occurred the last time the font was hot-reloaded, if the reloading failed.
+    /// The error is only returned once. See
+    /// [`TerminalBuilder::with_font_hot_reload`](struct.TerminalBuilder.html#method.with_font_hot_reload).
+    pub fn take_font_reload_error(&self) -> Option<FontError> {
+        self.font_reload_error.borrow_mut().take()
+    }
+
+    fn reload_fonts_if_modified(&self) {
+        let mut font_watchers = self.font_watchers.borrow_mut();
+        for (index, font_watcher) in font_watchers.iter_mut().enumerate() {
+            let result = match *font_watcher {
+                Some(ref mut watcher) => watcher.poll(),
+                None => None,
+            };
+            match result {
+                Some(Ok(mut new_font)) => {
+                    let mut font = self.get_font_by_handle_mut(FontHandle(index));
+                    // Make sure the meshes notice that the atlas has changed
+                    new_font.atlas_version = font.atlas_version.wrapping_add(1);
+                    new_font.fallback_character = font.fallback_character;
+                    if let Some(spread) = font.sdf_spread {
+                        new_font = new_font.with_sdf(spread);
+                    }
+                    new_font.keep_runtime_glyphs(&mut font);
+                    *font = new_font;
+                    *self.font_reload_error.borrow_mut() = None;
+                }
+                Some(Err(error)) => *self.font_reload_error.borrow_mut() = Some(error),
+                None => {}
+            }
+        }
+    }
+
     /// Flushes `TextBuffer`, taking it's character-grid and making it show for the next draw.
     ///
     /// This is quite a heavy function and it's calling should be avoided when unnecessary.
+    ///
+    /// If the `TextBuffer` is set to [fit the window](../text_buffer/struct.TextBuffer.html#method.set_fit_to_window),
+    /// it's resized to the current grid size first.
     pub fn flush(&self, text_buffer: &mut TextBuffer) {
-        text_buffer.swap_buffers(&self.font);
+        if text_buffer.is_fit_to_window() {
+            let grid_size = self.get_grid_size(text_buffer.font_handle);
+            // The grid size is always at least 1x1
+            if grid_size != (text_buffer.width, text_buffer.height) {
+                text_buffer.set_dimensions(self, grid_size);
+            }
+        }
+        let mut font = self.get_font_by_handle_mut(text_buffer.font_handle);
+        font.load_missing_characters(text_buffer.visible_chars().into_iter());
+        // The font may have been reloaded with a different size since the last flush
+        text_buffer.update_aspect_ratio(&font);
+        text_buffer.swap_buffers(&font);
     }
 
     /// Draws the `TextBuffer`, this should be called every time in the while-loop.
@@ -256,11 +482,7 @@ impl Terminal {
             &text_buffer.mesh,
             &text_buffer.background_mesh,
         ) {
-            if self.text_buffer_aspect_ratio
-                && text_buffer.aspect_ratio != display.get_aspect_ratio()
-            {
-                display.set_aspect_ratio(text_buffer.aspect_ratio);
-            }
+            let proj_matrix = self.get_proj_matrix(display, text_buffer);
             renderer::clear();
             let duration = SystemTime::now().duration_since(self.since_start).unwrap();
 
@@ -268,11 +490,24 @@ impl Terminal {
 
             renderer::draw(
                 self.get_background_program(),
-                display.proj_matrix.get(),
+                proj_matrix,
                 time,
                 background_mesh,
             );
-            renderer::draw(self.get_program(), display.proj_matrix.get(), time, mesh);
+            if let Some(ref cursor_mesh) = text_buffer.cursor_mesh {
+                renderer::draw(
+                    self.get_background_program(),
+                    proj_matrix,
+                    time,
+                    cursor_mesh,
+                );
+            }
+            renderer::draw(
+                self.get_program(text_buffer.font_handle),
+                proj_matrix,
+                time,
+                mesh,
+            );
         }
     }
 
@@ -287,11 +522,7 @@ impl Terminal {
                 &text_buffer.mesh,
                 &text_buffer.background_mesh,
             ) {
-                if self.text_buffer_aspect_ratio
-                    && text_buffer.aspect_ratio != display.get_aspect_ratio()
-                {
-                    display.set_aspect_ratio(text_buffer.aspect_ratio);
-                }
+                let proj_matrix = self.get_proj_matrix(display, text_buffer);
                 let duration = SystemTime::now().duration_since(self.since_start).unwrap();
 
                 let time =
@@ -299,15 +530,44 @@ impl Terminal {
 
                 renderer::draw(
                     self.get_background_program(),
-                    display.proj_matrix.get(),
+                    proj_matrix,
                     time,
                     background_mesh,
                 );
-                renderer::draw(self.get_program(), display.proj_matrix.get(), time, mesh);
+                if let Some(ref cursor_mesh) = text_buffer.cursor_mesh {
+                    renderer::draw(
+                        self.get_background_program(),
+                        proj_matrix,
+                        time,
+                        cursor_mesh,
+                    );
+                }
+                renderer::draw(
+                    self.get_program(text_buffer.font_handle),
+                    proj_matrix,
+                    time,
+                    mesh,
+                );
             }
         }
     }
 
+    /// Returns the projection matrix to draw the `TextBuffer` with
+    fn get_proj_matrix(&self, display: &Display, text_buffer: &TextBuffer) -> renderer::Matrix4 {
+        if text_buffer.is_fit_to_window() {
+            let (cell_width, cell_height) = self.get_cell_size(text_buffer.font_handle);
+            let content = (
+                text_buffer.width as u32 * cell_width,
+                text_buffer.height as u32 * cell_height,
+            );
+            return renderer::create_pixel_proj_matrix(display.get_dimensions(), content);
+        }
+        if self.text_buffer_aspect_ratio && text_buffer.aspect_ratio != display.get_aspect_ratio() {
+            display.set_aspect_ratio(text_buffer.aspect_ratio);
+        }
+        display.proj_matrix.get()
+    }
+
     /// Gets the current Input, must be retrieved every time you want new inputs. (ie. every frame)
     pub fn get_current_input(&self) -> Input {
         if let Some(ref display) = self.display {
@@ -338,19 +598,68 @@ impl Terminal {
         }
     }
 
+    /// Registers a font with the terminal, returning a handle that can be used to create `TextBuffer`s that use the font,
+    /// see [`TextBuffer::new_with_font`](../text_buffer/struct.TextBuffer.html#method.new_with_font).
+    ///
+    /// If font hot-reloading is enabled, the font is reloaded when its files are modified, same as the default font.
+    pub fn add_font(&self, font: Font) -> FontHandle {
+        let font_watcher = if self.font_hot_reload {
+            FontWatcher::new(&font)
+        } else {
+            None
+        };
+        let mut fonts = self.fonts.borrow_mut();
+        fonts.push(font);
+        self.font_watchers.borrow_mut().push(font_watcher);
+        FontHandle(fonts.len() - 1)
+    }
+
+    /// Returns the default font of the terminal, which is the font given to the `TerminalBuilder`.
+    pub fn get_font(&self) -> Ref<Font> {
+        self.get_font_by_handle(FontHandle::default())
+    }
+
+    /// Returns the default font of the terminal mutably, ie. to add custom glyphs with
+    /// [`add_custom_glyph`](../font/struct.Font.html#method.add_custom_glyph).
+    ///
+    /// Changes to the font's atlas are uploaded to a `TextBuffer` the next time it is flushed.
+    pub fn get_font_mut(&self) -> RefMut<Font> {
+        self.get_font_by_handle_mut(FontHandle::default())
+    }
+
+    /// Returns the font with the given handle, see [`add_font`](#method.add_font).
+    ///
+    /// Panics if the handle was not created by this terminal.
+    pub fn get_font_by_handle(&self, handle: FontHandle) -> Ref<Font> {
+        Ref::map(self.fonts.borrow(), |fonts| &fonts[handle.0])
+    }
+
+    /// Returns the font with the given handle mutably, see [`get_font_mut`](#method.get_font_mut).
+    ///
+    /// Panics if the handle was not created by this terminal.
+    pub fn get_font_by_handle_mut(&self, handle: FontHandle) -> RefMut<Font> {
+        RefMut::map(self.fonts.borrow_mut(), |fonts| &mut fonts[handle.0])
+    }
+
+    pub(crate) fn has_font(&self, handle: FontHandle) -> bool {
+        handle.0 < self.fonts.borrow().len()
+    }
+
     /// Returns the current fps; updates every second
     pub fn get_fps(&self) -> f32 {
         self.frame_counter.borrow().get_fps()
     }
 
-    pub(crate) fn get_program(&self) -> renderer::Program {
+    pub(crate) fn get_program(&self, font_handle: FontHandle) -> renderer::Program {
         if self.headless {
             panic!("Unable to get program from headless terminal");
         }
-        if !self.debug.get() {
-            self.program
-        } else {
+        if self.debug.get() {
             self.debug_program
+        } else if self.get_font_by_handle(font_handle).is_sdf() {
+            self.sdf_program
+        } else {
+            self.program
         }
     }
 
@@ -371,6 +680,12 @@ impl Terminal {
             display.update_virtual_keycode(keycode, pressed);
         }
     }
+
+    #[cfg(test)]
+    pub(crate) fn resize_window(&self, dimensions: (u32, u32)) {
+        self.window_dimensions.set(dimensions);
+        self.resized.set(true);
+    }
 }
 
 pub(crate) struct FrameCounter {
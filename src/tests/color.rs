@@ -0,0 +1,81 @@
+use text_buffer::Color;
+use text_buffer::parser::Parser;
+
+fn assert_close(color: Color, expected: [f32; 4]) {
+    for (value, expected) in color.to_array().iter().zip(expected.iter()) {
+        assert!((value - expected).abs() < 0.001, "{:?} != {:?}", color, expected);
+    }
+}
+
+#[test]
+fn test_color_from_hex() {
+    assert_eq!(Color::from_hex("#ff8000").unwrap().to_rgba_u8(), [255, 128, 0, 255]);
+    assert_eq!(Color::from_hex("00ff0080").unwrap().to_rgba_u8(), [0, 255, 0, 128]);
+    assert_eq!(Color::from_hex("#f80").unwrap().to_rgba_u8(), [255, 136, 0, 255]);
+    assert_eq!("#0008".parse::<Color>().unwrap().to_rgba_u8(), [0, 0, 0, 136]);
+    assert!(Color::from_hex("#ff80").is_ok());
+    assert!(Color::from_hex("#ff800").is_err());
+    assert!(Color::from_hex("#gg8000").is_err());
+    assert!(Color::from_hex("#ä00").is_err());
+}
+
+#[test]
+fn test_color_from_hsv_and_hsl() {
+    assert_close(Color::from_hsv(0.0, 1.0, 1.0), [1.0, 0.0, 0.0, 1.0]);
+    assert_close(Color::from_hsv(120.0, 1.0, 0.5), [0.0, 0.5, 0.0, 1.0]);
+    assert_close(Color::from_hsv(-120.0, 0.5, 1.0), [0.5, 0.5, 1.0, 1.0]);
+    assert_close(Color::from_hsl(60.0, 1.0, 0.5), [1.0, 1.0, 0.0, 1.0]);
+    assert_close(Color::from_hsl(300.0, 1.0, 0.25), [0.5, 0.0, 0.5, 1.0]);
+    assert_close(Color::from_hsl(180.0, 0.0, 0.75), [0.75, 0.75, 0.75, 1.0]);
+}
+
+#[test]
+fn test_color_xterm_palette() {
+    assert_eq!(Color::xterm(1), Color::RED);
+    assert_eq!(Color::xterm(15), Color::BRIGHT_WHITE);
+    assert_eq!(Color::xterm(16).to_rgba_u8(), [0, 0, 0, 255]);
+    assert_eq!(Color::xterm(196).to_rgba_u8(), [255, 0, 0, 255]);
+    assert_eq!(Color::xterm(110).to_rgba_u8(), [135, 175, 215, 255]);
+    assert_eq!(Color::xterm(231).to_rgba_u8(), [255, 255, 255, 255]);
+    assert_eq!(Color::xterm(232).to_rgba_u8(), [8, 8, 8, 255]);
+    assert_eq!(Color::xterm(255).to_rgba_u8(), [238, 238, 238, 255]);
+}
+
+#[test]
+fn test_color_lerp_and_blend() {
+    let black = Color::BLACK;
+    let white = Color::BRIGHT_WHITE;
+    assert_close(black.lerp(white, 0.25), [0.25, 0.25, 0.25, 1.0]);
+    assert_eq!(black.lerp(white, 2.0), white);
+
+    assert_close(black.blend(white.with_alpha(0.5)), [0.5, 0.5, 0.5, 1.0]);
+    assert_eq!(black.blend(white), white);
+    assert_eq!(Color::TRANSPARENT.blend(Color::TRANSPARENT), Color::TRANSPARENT);
+    assert_close(
+        Color::TRANSPARENT.blend(Color::new(1.0, 0.0, 0.0, 0.5)),
+        [1.0, 0.0, 0.0, 0.5],
+    );
+}
+
+#[test]
+fn test_color_array_compatibility() {
+    let color = Color::from([0.1, 0.2, 0.3, 0.4]);
+    assert_eq!(color, Color::new(0.1, 0.2, 0.3, 0.4));
+    assert_eq!(color, [0.1, 0.2, 0.3, 0.4]);
+    assert_eq!([0.1, 0.2, 0.3, 0.4], color);
+    let array: [f32; 4] = color.into();
+    assert_eq!(array, [0.1, 0.2, 0.3, 0.4]);
+
+    // Colors can still be indexed like arrays
+    let mut color = color;
+    assert_eq!(color[0], 0.1);
+    assert_eq!(color[3], 0.4);
+    color[3] = 1.0;
+    assert_eq!(color.a, 1.0);
+
+    let mut parser = Parser::new();
+    parser.add_color("red", Color::RED);
+    parser.add_color("blue", [0.0, 0.0, 1.0, 1.0]);
+    assert_eq!(*parser.get_color("red".to_owned()).unwrap(), Color::RED);
+    assert_eq!(*parser.get_color("blue".to_owned()).unwrap(), [0.0, 0.0, 1.0, 1.0]);
+}
@@ -1,4 +1,5 @@
-use font::Font;
+use font::{Font, FontError, GridMapping};
+use png::{BitDepth, ColorType, Encoder, HasParameters};
 
 static IOSEVKA_SFL: &'static str = include_str!("../../fonts/iosevka.sfl");
 static IOSEVKA_PNG: &'static [u8] = include_bytes!("../../fonts/iosevka.png");
@@ -64,4 +65,547 @@ fn test_font_single_character_offsets() {
 fn test_font_single_character_id() {
     let font = test_load_font();
     assert_eq!(font.get_character('a').unwrap().id, 97);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_font_unicode_character_does_not_alias() {
+    let font = test_load_font();
+    // 'š' is U+0161, which would alias to 'a' (0x61) if truncated to a byte
+    assert!(font.get_character('š').is_err());
+    assert!(font.get_character('ÿ').is_ok());
+}
+
+#[test]
+fn test_font_fallback_character() {
+    let mut font = test_load_font();
+    assert_eq!(font.get_fallback_character(), '?');
+    assert_eq!(
+        font.get_character_or_fallback('š'),
+        font.get_character('?').ok().as_ref()
+    );
+    font.set_fallback_character('#');
+    assert_eq!(
+        font.get_character_or_fallback('š'),
+        font.get_character('#').ok().as_ref()
+    );
+    assert_eq!(
+        font.get_character_or_fallback('a'),
+        font.get_character('a').ok().as_ref()
+    );
+}
+
+#[test]
+fn test_font_try_load_missing_file() {
+    match Font::try_load("fonts/does_not_exist.sfl") {
+        Err(FontError::Io(_)) => (),
+        _ => panic!("Expected an IO error"),
+    }
+}
+
+#[test]
+fn test_font_try_load_raw_malformed_sfl() {
+    match Font::try_load_raw("Broken\nnot numbers", IOSEVKA_PNG) {
+        Err(FontError::Sfl(_)) => (),
+        _ => panic!("Expected an .sfl error"),
+    }
+}
+
+#[test]
+fn test_font_try_load_raw_malformed_png() {
+    match Font::try_load_raw(IOSEVKA_SFL, &[0u8, 1, 2, 3][..]) {
+        Err(FontError::Png(_)) => (),
+        _ => panic!("Expected a PNG error"),
+    }
+}
+
+fn test_encode_png(color_type: ColorType, data: &[u8]) -> Vec<u8> {
+    let mut png_data = Vec::new();
+    {
+        let mut encoder = Encoder::new(&mut png_data, 2, 1);
+        encoder.set(color_type).set(BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(data).unwrap();
+    }
+    png_data
+}
+
+fn test_load_font_with_png(png_data: Vec<u8>) -> Font {
+    let sfl = "Test\n1 1\nimage.png\n1\n65 0 0 1 1 0 0 1\n";
+    Font::try_load_raw(sfl, &png_data[..]).unwrap()
+}
+
+#[test]
+fn test_font_load_grayscale_png() {
+    let font = test_load_font_with_png(test_encode_png(ColorType::Grayscale, &[0, 200]));
+    assert_eq!(font.image_buffer, vec![255, 255, 255, 0, 255, 255, 255, 200]);
+}
+
+#[test]
+fn test_font_load_grayscale_alpha_png() {
+    let font = test_load_font_with_png(test_encode_png(
+        ColorType::GrayscaleAlpha,
+        &[10, 20, 30, 40],
+    ));
+    assert_eq!(font.image_buffer, vec![10, 10, 10, 20, 30, 30, 30, 40]);
+}
+
+#[test]
+fn test_font_load_rgb_png() {
+    let font = test_load_font_with_png(test_encode_png(ColorType::RGB, &[1, 2, 3, 4, 5, 6]));
+    assert_eq!(font.image_buffer, vec![1, 2, 3, 255, 4, 5, 6, 255]);
+}
+
+#[test]
+fn test_font_from_grid_cp437() {
+    let font = Font::from_grid_raw(IOSEVKA_PNG, (24, 23), GridMapping::Cp437).unwrap();
+    assert_eq!(font.characters.keys().len(), 256);
+    assert_eq!(font.size, 24);
+    assert_eq!(font.line_height, 23);
+
+    // 'A' is 0x41; column 1, row 4
+    let a_char_data = font.get_character('A').unwrap();
+    assert_eq!(a_char_data.x1, 24.0 / 393.0);
+    assert_eq!(a_char_data.y1, 92.0 / 374.0);
+    assert_eq!(a_char_data.width, 24);
+    assert_eq!(a_char_data.height, 23);
+
+    // 0xC9 is '╔'; column 9, row 12
+    let box_char_data = font.get_character('╔').unwrap();
+    assert_eq!(box_char_data.x1, 216.0 / 393.0);
+    assert_eq!(box_char_data.y1, 276.0 / 374.0);
+}
+
+#[test]
+fn test_font_from_grid_custom_mapping() {
+    let mapping = GridMapping::Custom(vec!['a', 'b', 'c']);
+    let font = Font::from_grid_raw(IOSEVKA_PNG, (24, 23), mapping).unwrap();
+    assert_eq!(font.characters.keys().len(), 3);
+    assert_eq!(font.get_character('c').unwrap().x1, 48.0 / 393.0);
+    assert!(font.get_character('d').is_err());
+}
+
+#[test]
+fn test_font_from_grid_invalid_cell_dimensions() {
+    match Font::from_grid_raw(IOSEVKA_PNG, (0, 23), GridMapping::Cp437) {
+        Err(FontError::InvalidCellDimensions(0, 23)) => (),
+        _ => panic!("Expected an invalid cell dimensions error"),
+    }
+}
+
+static SOURCE_CODE_PRO: &'static str = "fonts/SourceCodePro-Medium.ttf";
+
+#[test]
+fn test_font_truetype_rasterizes_ascii() {
+    let font = Font::load_truetype(SOURCE_CODE_PRO, 24);
+    assert!(font.get_character('a').is_ok());
+    assert!(font.get_character('~').is_ok());
+    assert!(font.get_character('Ā').is_err());
+    assert_eq!(font.name, "SourceCodePro-Medium");
+}
+
+#[test]
+fn test_font_truetype_lazily_rasterizes_characters() {
+    let mut font = Font::load_truetype(SOURCE_CODE_PRO, 24);
+    let atlas_version = font.atlas_version;
+    font.load_missing_characters("Ā┼→".chars());
+    assert!(font.get_character('Ā').is_ok());
+    assert!(font.get_character('┼').is_ok());
+    assert!(font.get_character('→').is_ok());
+    assert_ne!(font.atlas_version, atlas_version);
+    assert_eq!(font.image_buffer.len(), (font.width * font.height * 4) as usize);
+}
+
+#[test]
+fn test_font_truetype_invalid_data() {
+    match Font::try_load_truetype_raw(vec![0u8, 1, 2, 3], 24) {
+        Err(FontError::TrueType(_)) => (),
+        _ => panic!("Expected a TrueType error"),
+    }
+}
+
+#[test]
+fn test_font_from_psf1_with_unicode_table() {
+    let mut psf = vec![0x36, 0x04, 0x02, 2];
+    // 256 glyphs, 8x2 pixels each; glyph 1 has its top-left pixel set
+    let mut glyphs = vec![0u8; 256 * 2];
+    glyphs[2] = 0x80;
+    psf.extend(glyphs);
+    // Glyph 0 maps to 'a', glyph 1 maps to 'Ā' and '┼'
+    psf.extend(&[0x61, 0x00, 0xFF, 0xFF]);
+    psf.extend(&[0x00, 0x01, 0x3C, 0x25, 0xFF, 0xFF]);
+    for _ in 2..256 {
+        psf.extend(&[0xFF, 0xFF]);
+    }
+
+    let font = Font::from_psf_raw(&psf).unwrap();
+    assert_eq!(font.size, 8);
+    assert_eq!(font.line_height, 2);
+    assert_eq!(font.characters.keys().len(), 3);
+    assert_eq!(
+        font.get_character('Ā').unwrap().x1,
+        font.get_character('┼').unwrap().x1
+    );
+
+    let char_data = font.get_character('Ā').unwrap();
+    let x = (char_data.x1 * font.width as f32).round() as u32;
+    let y = (char_data.y1 * font.height as f32).round() as u32;
+    assert_eq!(font.image_buffer[((y * font.width + x) * 4 + 3) as usize], 255);
+    assert_eq!(font.image_buffer[((y * font.width + x + 1) * 4 + 3) as usize], 0);
+}
+
+#[test]
+fn test_font_from_psf2_without_unicode_table() {
+    let mut psf = vec![0x72, 0xb5, 0x4a, 0x86];
+    for value in &[0u32, 32, 0, 256, 4, 2, 10] {
+        psf.extend(&[*value as u8, (*value >> 8) as u8, (*value >> 16) as u8, 0]);
+    }
+    psf.extend(vec![0u8; 256 * 4]);
+
+    let font = Font::from_psf_raw(&psf).unwrap();
+    assert_eq!(font.size, 10);
+    assert_eq!(font.line_height, 2);
+    // Mapped in code page 437 order
+    assert!(font.get_character('╬').is_ok());
+    assert_eq!(font.characters.keys().len(), 256);
+}
+
+#[test]
+fn test_font_from_psf_invalid_data() {
+    match Font::from_psf_raw(&[1, 2, 3, 4]) {
+        Err(FontError::Psf(_)) => (),
+        _ => panic!("Expected a PSF error"),
+    }
+
+    // PSF2 headers with empty glyphs, with huge glyphs and with more glyphs than there is data for
+    for header in &[
+        [0u32, 32, 0, 1, 0, 0, 0],
+        [0, 32, 0, 1, 16, 1, 100_000],
+        [0, 32, 0, 0xFFFF_FFFF, 4, 2, 10],
+    ] {
+        let mut psf = vec![0x72, 0xb5, 0x4a, 0x86];
+        for value in header.iter() {
+            psf.extend(&[
+                *value as u8,
+                (*value >> 8) as u8,
+                (*value >> 16) as u8,
+                (*value >> 24) as u8,
+            ]);
+        }
+        psf.extend(vec![0u8; 16]);
+        match Font::from_psf_raw(&psf) {
+            Err(FontError::Psf(_)) => (),
+            _ => panic!("Expected a PSF error"),
+        }
+    }
+}
+
+#[test]
+fn test_font_from_bdf() {
+    let bdf = "STARTFONT 2.1
+FONT -test-font
+SIZE 8 75 75
+FONTBOUNDINGBOX 4 6 0 -2
+STARTPROPERTIES 2
+FONT_ASCENT 4
+FONT_DESCENT 2
+ENDPROPERTIES
+CHARS 2
+STARTCHAR A
+ENCODING 65
+DWIDTH 4 0
+BBX 3 4 0 0
+BITMAP
+40
+A0
+E0
+A0
+ENDCHAR
+STARTCHAR unencoded
+ENCODING -1
+BBX 1 1 0 0
+BITMAP
+80
+ENDCHAR
+ENDFONT
+";
+    let font = Font::from_bdf_raw(bdf).unwrap();
+    assert_eq!(font.name, "-test-font");
+    assert_eq!(font.size, 4);
+    assert_eq!(font.line_height, 6);
+    assert_eq!(font.characters.keys().len(), 1);
+
+    let char_data = font.get_character('A').unwrap();
+    assert_eq!(char_data.width, 3);
+    assert_eq!(char_data.height, 4);
+    assert_eq!(char_data.y_off, 0);
+    // Second pixel of the top row is set, first is not
+    assert_eq!(font.image_buffer[3], 0);
+    assert_eq!(font.image_buffer[7], 255);
+}
+
+#[test]
+fn test_font_from_bdf_missing_bounding_box() {
+    match Font::from_bdf_raw("STARTFONT 2.1\nSTARTCHAR A\nENDCHAR\n") {
+        Err(FontError::Bdf(_)) => (),
+        _ => panic!("Expected a BDF error"),
+    }
+}
+
+#[test]
+fn test_font_from_bdf_negative_dimensions() {
+    for bdf in &[
+        "STARTFONT 2.1\nFONTBOUNDINGBOX -4 6 0 -2\n",
+        "STARTFONT 2.1\nFONTBOUNDINGBOX 4 6 0 -2\nSTARTCHAR A\nENCODING 65\nBBX 3 -4 0 0\nBITMAP\nENDCHAR\n",
+    ] {
+        match Font::from_bdf_raw(*bdf) {
+            Err(FontError::Bdf(_)) => (),
+            _ => panic!("Expected a BDF error"),
+        }
+    }
+}
+
+#[test]
+fn test_font_from_bdf_out_of_range_sizes() {
+    for bdf in &[
+        // A huge glyph without any rows
+        "STARTFONT 2.1\nFONTBOUNDINGBOX 4 6 0 -2\nSTARTCHAR A\nENCODING 65\nBBX 2000000000 0 0 0\nBITMAP\nENDCHAR\n",
+        "STARTFONT 2.1\nFONTBOUNDINGBOX 100000 6 0 -2\n",
+        // Line heights that are negative or overflow
+        "STARTFONT 2.1\nFONTBOUNDINGBOX 4 6 0 -2\nFONT_ASCENT 1\nFONT_DESCENT -5\n",
+        "STARTFONT 2.1\nFONTBOUNDINGBOX 4 6 0 -2\nFONT_ASCENT 2147483647\nFONT_DESCENT 1\n",
+        // Offsets that overflow
+        "STARTFONT 2.1\nFONTBOUNDINGBOX 4 6 0 2147483647\nSTARTCHAR A\nENCODING 65\nBBX 1 0 0 0\nENDCHAR\n",
+        "STARTFONT 2.1\nFONTBOUNDINGBOX 4 6 1 -2\nSTARTCHAR A\nENCODING 65\nBBX 1 0 -2147483648 0\nENDCHAR\n",
+    ] {
+        match Font::from_bdf_raw(*bdf) {
+            Err(FontError::Bdf(_)) => (),
+            _ => panic!("Expected a BDF error"),
+        }
+    }
+}
+
+#[test]
+fn test_font_from_bdf_too_many_glyphs_for_atlas() {
+    // A single tall glyph makes every row of the atlas tall
+    let mut bdf = "STARTFONT 2.1\nFONTBOUNDINGBOX 1 256 0 0\nSTARTCHAR tall\nENCODING 0\nBBX 1 256 0 0\nBITMAP\n"
+        .to_owned();
+    bdf.push_str(&"80\n".repeat(256));
+    bdf.push_str("ENDCHAR\n");
+    for encoding in 1..2000 {
+        bdf.push_str(&format!(
+            "STARTCHAR empty\nENCODING {}\nBBX 1 0 0 0\nBITMAP\nENDCHAR\n",
+            encoding
+        ));
+    }
+    match Font::from_bdf_raw(bdf) {
+        Err(FontError::Bdf(_)) => (),
+        _ => panic!("Expected a BDF error"),
+    }
+}
+
+#[test]
+fn test_font_fallback_font_fills_missing_glyphs() {
+    let mut font = test_load_font();
+    let (old_width, old_height) = (font.width, font.height);
+    let old_a_char_data = font.get_character('a').unwrap();
+    let fallback = Font::from_grid_raw(
+        IOSEVKA_PNG,
+        (24, 23),
+        GridMapping::Custom(vec!['a', 'Ā']),
+    ).unwrap();
+    font.add_fallback_font(fallback);
+
+    // Nothing is copied until the glyphs are needed
+    assert_eq!((font.width, font.height), (old_width, old_height));
+    assert!(font.get_character('Ā').is_err());
+
+    font.load_missing_characters("aĀ".chars());
+    assert_eq!(font.width, old_width);
+    assert!(font.height > old_height);
+    assert_eq!(font.image_buffer.len(), (font.width * font.height * 4) as usize);
+
+    // Existing glyphs are kept, but moved in UV space as the atlas grew
+    let a_char_data = font.get_character('a').unwrap();
+    assert_eq!(a_char_data.width, old_a_char_data.width);
+    assert_eq!(
+        (a_char_data.y1 * font.height as f32).round(),
+        (old_a_char_data.y1 * old_height as f32).round()
+    );
+
+    // Missing glyphs are taken from the fallback, scaled to the size of the font
+    let fallback_char_data = font.get_character('Ā').unwrap();
+    assert_eq!(fallback_char_data.width, 32);
+    assert_eq!(fallback_char_data.height, 56);
+    assert_eq!(fallback_char_data.x1, 0.0);
+    assert_eq!(
+        (fallback_char_data.y1 * font.height as f32).round() as u32,
+        old_height
+    );
+}
+
+#[test]
+fn test_font_fallback_font_order() {
+    let red: Vec<u8> = (0..24 * 23).flat_map(|_| vec![255, 0, 0, 255]).collect();
+    let blue: Vec<u8> = (0..24 * 23).flat_map(|_| vec![0, 0, 255, 255]).collect();
+    let mut first = Font::from_grid_raw(IOSEVKA_PNG, (24, 23), GridMapping::Custom(vec!['x'])).unwrap();
+    first.set_custom_glyph('Ā', (24, 23), &red).unwrap();
+    let mut second = Font::from_grid_raw(IOSEVKA_PNG, (24, 23), GridMapping::Custom(vec!['x'])).unwrap();
+    second.set_custom_glyph('Ā', (24, 23), &blue).unwrap();
+    second.set_custom_glyph('ā', (24, 23), &blue).unwrap();
+    let mut font = test_load_font()
+        .with_fallback_font(first)
+        .with_fallback_font(second);
+    font.load_missing_characters("Āā".chars());
+
+    let first_pixel = |font: &Font, character: char| {
+        let character_data = font.get_character(character).unwrap();
+        let x = (character_data.x1 * font.width as f32).round() as u32;
+        let y = (character_data.y1 * font.height as f32).round() as u32;
+        let index = ((y * font.width + x) * 4) as usize;
+        font.image_buffer[index..index + 4].to_vec()
+    };
+    assert_eq!(first_pixel(&font, 'Ā'), vec![255, 0, 0, 255]);
+    assert_eq!(first_pixel(&font, 'ā'), vec![0, 0, 255, 255]);
+}
+
+#[test]
+fn test_font_truetype_fallback_font_rasterizes_missing_glyphs() {
+    let fallback = Font::load_truetype(SOURCE_CODE_PRO, 24);
+    assert!(fallback.get_character('┼').is_err());
+    let mut font = test_load_font().with_fallback_font(fallback);
+    assert!(font.get_character('┼').is_err());
+
+    font.load_missing_characters("a┼".chars());
+    assert!(font.get_character('┼').is_ok());
+    assert!(font.fallback_fonts[0].get_character('┼').is_ok());
+
+    // Characters that no font has are drawn with the fallback character
+    font.load_missing_characters("\u{10FFFD}".chars());
+    assert!(font.get_character('\u{10FFFD}').is_err());
+    assert_eq!(
+        font.get_character_or_fallback('\u{10FFFD}'),
+        font.get_character_or_fallback(font.fallback_character)
+    );
+}
+
+#[test]
+fn test_font_add_custom_glyph() {
+    let mut font = test_load_font();
+    let (old_width, old_height) = (font.width, font.height);
+    let atlas_version = font.atlas_version;
+    let pixels: Vec<u8> = (0..4 * 2).flat_map(|_| vec![10, 20, 30, 40]).collect();
+
+    let first = font.add_custom_glyph((4, 2), &pixels).unwrap();
+    let second = font.add_custom_glyph((4, 2), &pixels).unwrap();
+    assert_eq!(first, '\u{E000}');
+    assert_eq!(second, '\u{E001}');
+    assert_ne!(font.atlas_version, atlas_version);
+    assert_eq!(font.width, old_width);
+    assert!(font.height > old_height);
+
+    // Custom glyphs fill a whole cell
+    let char_data = font.get_character(first).unwrap();
+    assert_eq!(char_data.width, font.size as i32);
+    assert_eq!(char_data.height, font.line_height as i32);
+
+    let x = (char_data.x1 * font.width as f32).round() as u32;
+    let y = (char_data.y1 * font.height as f32).round() as u32;
+    assert_eq!(y, old_height);
+    let index = ((y * font.width + x) * 4) as usize;
+    assert_eq!(&font.image_buffer[index..index + 4], &[10, 20, 30, 40]);
+
+    // Both glyphs are on the same shelf
+    let second_char_data = font.get_character(second).unwrap();
+    assert_eq!(second_char_data.y1, char_data.y1);
+    assert!(second_char_data.x1 > char_data.x2);
+}
+
+#[test]
+fn test_font_set_custom_glyph_size_mismatch() {
+    let mut font = test_load_font();
+    match font.set_custom_glyph('a', (4, 4), &[0; 4]) {
+        Err(FontError::SizeMismatch { expected: 64, actual: 4 }) => (),
+        _ => panic!("Expected a size mismatch error"),
+    }
+}
+
+#[test]
+fn test_font_glyph_metrics() {
+    let font = test_load_font();
+    let metrics = font.get_glyph_metrics('a').unwrap();
+    assert_eq!(metrics.advance, 32);
+    assert_eq!(metrics.bearing, (2, 32 - font.get_min_offset_y()));
+    assert_eq!(metrics.dimensions, (18, 24));
+    assert_eq!(metrics.uv_rect, font.get_character('a').unwrap().get_uv_rect());
+    assert!(font.get_glyph_metrics('\u{E000}').is_none());
+}
+
+#[test]
+fn test_font_has_glyph_and_characters() {
+    let font = test_load_font();
+    assert!(font.has_glyph('a'));
+    assert!(!font.has_glyph('\u{E000}'));
+    assert_eq!(font.characters().count(), 191);
+    assert!(font.characters().all(|character| font.has_glyph(character)));
+}
+
+#[test]
+fn test_font_atlas_dimensions() {
+    let font = test_load_font();
+    assert_eq!(font.get_atlas_dimensions(), (393, 374));
+    assert_eq!(font.get_atlas_image().len(), 393 * 374 * 4);
+}
+
+#[test]
+fn test_font_with_sdf() {
+    // A 4x2 tileset with a solid 2x2 cell and an empty 2x2 cell
+    let mut png_data = Vec::new();
+    {
+        let mut encoder = Encoder::new(&mut png_data, 4, 2);
+        encoder.set(ColorType::Grayscale).set(BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        writer
+            .write_image_data(&[255, 255, 0, 0, 255, 255, 0, 0])
+            .unwrap();
+    }
+    let mapping = GridMapping::Custom(vec!['#', ' ']);
+    let font = Font::from_grid_raw(&png_data[..], (2, 2), mapping).unwrap();
+    assert!(!font.is_sdf());
+
+    let font = font.with_sdf(2);
+    assert!(font.is_sdf());
+    let metrics = font.get_glyph_metrics('#').unwrap();
+    assert_eq!(metrics.dimensions, (6, 6));
+    assert_eq!(metrics.bearing, (-2, -2));
+
+    let (atlas_width, atlas_height) = font.get_atlas_dimensions();
+    let alpha_at = |uv_x: f32, uv_y: f32, x: u32, y: u32| {
+        let atlas_x = (uv_x * atlas_width as f32).round() as u32 + x;
+        let atlas_y = (uv_y * atlas_height as f32).round() as u32 + y;
+        font.get_atlas_image()[((atlas_y * atlas_width + atlas_x) * 4 + 3) as usize]
+    };
+    let (x1, y1, _, _) = metrics.uv_rect;
+    assert!(alpha_at(x1, y1, 2, 2) > 128);
+    assert!(alpha_at(x1, y1, 0, 0) < 128);
+
+    let (x1, y1, _, _) = font.get_glyph_metrics(' ').unwrap().uv_rect;
+    assert!(alpha_at(x1, y1, 3, 3) < 128);
+}
+
+#[test]
+fn test_font_truetype_sdf_pads_lazily_rasterized_glyphs() {
+    let plain = Font::load_truetype(SOURCE_CODE_PRO, 16);
+    let mut font = Font::load_truetype(SOURCE_CODE_PRO, 16).with_sdf(3);
+    let plain_a = plain.get_glyph_metrics('a').unwrap();
+    let sdf_a = font.get_glyph_metrics('a').unwrap();
+    assert_eq!(sdf_a.dimensions.0, plain_a.dimensions.0 + 6);
+    assert_eq!(sdf_a.bearing.0, plain_a.bearing.0 - 3);
+
+    font.load_missing_characters(vec!['é'].into_iter());
+    let mut plain = plain;
+    plain.load_missing_characters(vec!['é'].into_iter());
+    assert_eq!(
+        font.get_glyph_metrics('é').unwrap().dimensions.1,
+        plain.get_glyph_metrics('é').unwrap().dimensions.1 + 6
+    );
+}
@@ -0,0 +1,131 @@
+use super::{random_color, test_row_text, test_setup_text_buffer};
+use text_buffer::layout::{HorizontalAlignment, TextLayout, TextOverflow, VerticalAlignment};
+use text_buffer::parser::Parser;
+
+#[test]
+fn test_layout_word_wrap() {
+    let mut text_buffer = test_setup_text_buffer((10, 4));
+    let lines =
+        TextLayout::new((0, 0), (10, 4)).write(&mut text_buffer, "the quick brown fox jumps");
+    assert_eq!(lines, 3);
+    assert_eq!(test_row_text(&text_buffer, 0), "the quick ");
+    assert_eq!(test_row_text(&text_buffer, 1), "brown fox ");
+    assert_eq!(test_row_text(&text_buffer, 2), "jumps     ");
+}
+
+#[test]
+fn test_layout_paragraphs_and_position() {
+    let mut text_buffer = test_setup_text_buffer((6, 4));
+    let lines = TextLayout::new((1, 1), (5, 3)).write(&mut text_buffer, "ab\n\ncd");
+    assert_eq!(lines, 3);
+    assert_eq!(test_row_text(&text_buffer, 0), "      ");
+    assert_eq!(test_row_text(&text_buffer, 1), " ab   ");
+    assert_eq!(test_row_text(&text_buffer, 2), "      ");
+    assert_eq!(test_row_text(&text_buffer, 3), " cd   ");
+    assert_eq!(text_buffer.get_cursor_position(), (0, 0));
+}
+
+#[test]
+fn test_layout_hyphenation() {
+    let mut text_buffer = test_setup_text_buffer((4, 3));
+    let lines = TextLayout::new((0, 0), (4, 3)).write(&mut text_buffer, "a abcdefg");
+    assert_eq!(lines, 3);
+    assert_eq!(test_row_text(&text_buffer, 0), "a   ");
+    assert_eq!(test_row_text(&text_buffer, 1), "abc-");
+    assert_eq!(test_row_text(&text_buffer, 2), "defg");
+
+    let mut text_buffer = test_setup_text_buffer((4, 2));
+    TextLayout::new((0, 0), (4, 2))
+        .with_hyphenation(false)
+        .write(&mut text_buffer, "abcdefg");
+    assert_eq!(test_row_text(&text_buffer, 0), "abcd");
+    assert_eq!(test_row_text(&text_buffer, 1), "efg ");
+}
+
+#[test]
+fn test_layout_horizontal_alignment() {
+    let mut text_buffer = test_setup_text_buffer((9, 3));
+    TextLayout::new((0, 0), (9, 1))
+        .with_horizontal_alignment(HorizontalAlignment::Center)
+        .write(&mut text_buffer, "abc");
+    TextLayout::new((0, 1), (9, 1))
+        .with_horizontal_alignment(HorizontalAlignment::Right)
+        .write(&mut text_buffer, "abc");
+    assert_eq!(test_row_text(&text_buffer, 0), "   abc   ");
+    assert_eq!(test_row_text(&text_buffer, 1), "      abc");
+}
+
+#[test]
+fn test_layout_justify() {
+    let mut text_buffer = test_setup_text_buffer((9, 2));
+    TextLayout::new((0, 0), (9, 2))
+        .with_horizontal_alignment(HorizontalAlignment::Justify)
+        .write(&mut text_buffer, "a b c dddd e");
+    assert_eq!(test_row_text(&text_buffer, 0), "a   b   c");
+    assert_eq!(test_row_text(&text_buffer, 1), "dddd e   ");
+}
+
+#[test]
+fn test_layout_vertical_alignment() {
+    let mut text_buffer = test_setup_text_buffer((3, 3));
+    TextLayout::new((0, 0), (1, 3))
+        .with_vertical_alignment(VerticalAlignment::Middle)
+        .write(&mut text_buffer, "a");
+    TextLayout::new((2, 0), (1, 3))
+        .with_vertical_alignment(VerticalAlignment::Bottom)
+        .write(&mut text_buffer, "b");
+    assert_eq!(test_row_text(&text_buffer, 0), "   ");
+    assert_eq!(test_row_text(&text_buffer, 1), "a  ");
+    assert_eq!(test_row_text(&text_buffer, 2), "  b");
+}
+
+#[test]
+fn test_layout_overflow() {
+    let mut text_buffer = test_setup_text_buffer((6, 2));
+    let lines = TextLayout::new((0, 0), (6, 1)).write(&mut text_buffer, "abc defgh");
+    assert_eq!(lines, 1);
+    assert_eq!(test_row_text(&text_buffer, 0), "abc   ");
+    assert_eq!(test_row_text(&text_buffer, 1), "      ");
+
+    let lines = TextLayout::new((0, 1), (6, 1))
+        .with_overflow(TextOverflow::Ellipsis)
+        .write(&mut text_buffer, "abcd efgh");
+    assert_eq!(lines, 1);
+    assert_eq!(test_row_text(&text_buffer, 1), "abc...");
+}
+
+#[test]
+fn test_layout_wide_characters() {
+    let mut text_buffer = test_setup_text_buffer((5, 3));
+    let lines = TextLayout::new((0, 0), (5, 3)).write(&mut text_buffer, "e\u{301}b 日本語");
+    assert_eq!(lines, 3);
+    assert_eq!(test_row_text(&text_buffer, 0), "eb   ");
+    assert_eq!(text_buffer.get_character(0, 0).get_grapheme(), "e\u{301}");
+    // Wide characters are broken between without hyphens, and never cut in half
+    assert_eq!(test_row_text(&text_buffer, 1), "日 本  ");
+    assert_eq!(test_row_text(&text_buffer, 2), "語    ");
+    assert_eq!(text_buffer.get_character(2, 1).get_width(), 2);
+    assert_eq!(text_buffer.get_character(3, 1).get_width(), 0);
+
+    // The half of the first character that would be left of the text buffer is emptied
+    let mut text_buffer = test_setup_text_buffer((5, 1));
+    TextLayout::new((-1, 0), (4, 1)).write(&mut text_buffer, "日本");
+    assert_eq!(test_row_text(&text_buffer, 0), " 本   ");
+    assert_eq!(text_buffer.get_character(0, 0).get_width(), 1);
+    assert_eq!(text_buffer.get_character(1, 0).get_width(), 2);
+}
+
+#[test]
+fn test_layout_parser_markup() {
+    let mut text_buffer = test_setup_text_buffer((4, 2));
+    let mut parser = Parser::new();
+    let color = random_color();
+    parser.add_color("test", color);
+    TextLayout::new((0, 0), (4, 2)).write_parsed(&mut text_buffer, &parser, "a [fg=test]bb[/fg] c");
+
+    assert_eq!(test_row_text(&text_buffer, 0), "a bb");
+    assert_eq!(text_buffer.get_character(0, 0).get_fg_color(), [1.0; 4]);
+    assert_eq!(text_buffer.get_character(2, 0).get_fg_color(), color);
+    assert_eq!(text_buffer.get_character(0, 1).get_fg_color(), [1.0; 4]);
+    assert_eq!(text_buffer.get_cursor_fg_color(), [1.0; 4]);
+}
@@ -6,9 +6,13 @@ mod terminal;
 mod input;
 mod text_buffer;
 mod parser;
+mod layout;
+mod view;
 mod font;
+mod color;
 
 use rand;
+use unicode_width::UnicodeWidthChar;
 
 #[test]
 fn test_for_gl_error() {
@@ -51,6 +55,12 @@ fn test_setup_text_buffer_with_terminal(dimensions: (i32, i32)) -> (TextBuffer,
     }, terminal)
 }
 
+fn test_row_text(text_buffer: &TextBuffer, y: i32) -> String {
+    (0..text_buffer.width)
+        .map(|x| text_buffer.get_character(x, y).get_char())
+        .collect()
+}
+
 fn run_multiple_times<F: Fn()>(how_many_times: u32, f: F) {
     for _ in 0..how_many_times {
         f();
@@ -65,3 +75,13 @@ fn random_color() -> [f32; 4] {
         rand::random::<f32>(),
     ]
 }
+
+/// Returns a random char that takes a single cell in a `TextBuffer`.
+fn random_narrow_char() -> char {
+    loop {
+        let character = rand::random::<char>();
+        if UnicodeWidthChar::width(character) == Some(1) {
+            return character;
+        }
+    }
+}
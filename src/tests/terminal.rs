@@ -1,7 +1,14 @@
 use super::{run_multiple_times, test_setup_open_terminal};
-use terminal::FrameCounter;
+use font::{Font, FontError, GridMapping};
+use png::{BitDepth, ColorType, Encoder, HasParameters};
+use terminal::{FontHandle, FrameCounter, TerminalBuilder};
+use text_buffer::TextBuffer;
 use rand;
 use rand::distributions::{Range, Sample};
+use std::env;
+use std::fs::{self, File};
+use std::path::Path;
+use std::process;
 use std::time::Duration;
 use std::thread;
 
@@ -17,13 +24,13 @@ fn test_terminal_open_refresh_and_close() {
 fn test_terminal_programs_debug_shaders() {
     let terminal = test_setup_open_terminal();
     if !terminal.headless {
-        let program = terminal.get_program();
+        let program = terminal.get_program(FontHandle::default());
         let background_program = terminal.get_background_program();
 
         assert_ne!(program, background_program);
 
         terminal.set_debug(true);
-        let debug_program = terminal.get_program();
+        let debug_program = terminal.get_program(FontHandle::default());
         let debug_background_program = terminal.get_background_program();
 
         assert_eq!(debug_program, debug_background_program);
@@ -51,3 +58,137 @@ pub fn test_terminal_frame_counter() {
         assert_eq!(frame_counter.get_fps(), target_fps as f32 + 1.0);
     })
 }
+
+fn test_write_grid_png(path: &Path, data: &[u8]) {
+    let file = File::create(path).unwrap();
+    let mut encoder = Encoder::new(file, 2, 1);
+    encoder.set(ColorType::Grayscale).set(BitDepth::Eight);
+    let mut writer = encoder.write_header().unwrap();
+    writer.write_image_data(data).unwrap();
+}
+
+#[test]
+fn test_terminal_font_hot_reload() {
+    let dir = env::temp_dir().join(format!("glerminal-hot-reload-{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("grid.png");
+    test_write_grid_png(&path, &[0, 200]);
+
+    let mapping = GridMapping::Custom(vec!['c']);
+    let fallback = Font::from_grid("fonts/iosevka.png", (24, 23), mapping).unwrap();
+    let mut font = Font::from_grid(&path, (1, 1), GridMapping::Custom(vec!['a', 'b']))
+        .unwrap()
+        .with_fallback_font(fallback);
+    let custom = font.add_custom_glyph((1, 1), &[10, 20, 30, 40]).unwrap();
+    let terminal = TerminalBuilder::new()
+        .with_font(font)
+        .with_font_hot_reload(true)
+        .with_headless(true)
+        .build();
+    let atlas_version = terminal.get_font().atlas_version;
+
+    // A broken image is reported and the old font is kept
+    fs::write(&path, vec![0u8, 1, 2, 3]).unwrap();
+    terminal.refresh();
+    match terminal.take_font_reload_error() {
+        Some(FontError::Png(_)) => (),
+        _ => panic!("Expected a PNG error"),
+    }
+    assert!(terminal.take_font_reload_error().is_none());
+    assert_eq!(terminal.get_font().image_buffer[7], 200);
+
+    test_write_grid_png(&path, &[0, 100]);
+    terminal.refresh();
+    assert!(terminal.take_font_reload_error().is_none());
+    assert_eq!(terminal.get_font().image_buffer[7], 100);
+    assert_ne!(terminal.get_font().atlas_version, atlas_version);
+
+    // Fallback fonts and custom glyphs survive the reload
+    let mut font = terminal.get_font_mut();
+    assert_eq!(font.fallback_fonts.len(), 1);
+    font.load_missing_characters("c".chars());
+    assert!(font.get_character('c').is_ok());
+    let character_data = font.get_character(custom).unwrap();
+    let x = (character_data.x1 * font.width as f32).round() as u32;
+    let y = (character_data.y1 * font.height as f32).round() as u32;
+    let index = ((y * font.width + x) * 4) as usize;
+    assert_eq!(&font.image_buffer[index..index + 4], &[10, 20, 30, 40]);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_terminal_font_hot_reload_disabled() {
+    let dir = env::temp_dir().join(format!("glerminal-no-hot-reload-{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("grid.png");
+    test_write_grid_png(&path, &[0, 200]);
+
+    let font = Font::from_grid(&path, (1, 1), GridMapping::Custom(vec!['a', 'b'])).unwrap();
+    let terminal = TerminalBuilder::new()
+        .with_font(font)
+        .with_headless(true)
+        .build();
+
+    test_write_grid_png(&path, &[0, 100]);
+    terminal.refresh();
+    assert!(terminal.take_font_reload_error().is_none());
+    assert_eq!(terminal.get_font().image_buffer[7], 200);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_terminal_grid_size() {
+    let terminal = TerminalBuilder::new()
+        .with_headless(true)
+        .with_dimensions((800, 600))
+        .build();
+    let (cell_width, cell_height) = {
+        let font = terminal.get_font();
+        (font.size, font.line_height)
+    };
+    assert!(!terminal.was_resized());
+    assert_eq!(terminal.get_window_dimensions(), (800, 600));
+    assert_eq!(
+        terminal.get_grid_size(FontHandle::default()),
+        ((800 / cell_width) as i32, (600 / cell_height) as i32)
+    );
+
+    terminal.set_cell_scale(2);
+    assert_eq!(
+        terminal.get_grid_size(FontHandle::default()),
+        ((800 / (cell_width * 2)) as i32, (600 / (cell_height * 2)) as i32)
+    );
+
+    terminal.resize_window((1, 1));
+    assert!(terminal.was_resized());
+    assert_eq!(terminal.get_grid_size(FontHandle::default()), (1, 1));
+
+    terminal.refresh();
+    assert!(!terminal.was_resized());
+}
+
+#[test]
+fn test_terminal_fit_text_buffer_to_window() {
+    let terminal = TerminalBuilder::new()
+        .with_headless(true)
+        .with_dimensions((800, 600))
+        .build();
+    let mut text_buffer = TextBuffer::new(&terminal, (2, 2)).unwrap();
+    text_buffer.write("ab");
+
+    terminal.flush(&mut text_buffer);
+    assert_eq!((text_buffer.width, text_buffer.height), (2, 2));
+
+    text_buffer.set_fit_to_window(true);
+    terminal.flush(&mut text_buffer);
+    let grid_size = terminal.get_grid_size(FontHandle::default());
+    assert_eq!((text_buffer.width, text_buffer.height), grid_size);
+    assert_eq!(text_buffer.get_character(1, 0).get_char(), 'b');
+
+    terminal.resize_window((1, 1));
+    terminal.flush(&mut text_buffer);
+    assert_eq!((text_buffer.width, text_buffer.height), (1, 1));
+    assert_eq!(text_buffer.get_character(0, 0).get_char(), 'a');
+}
@@ -1,5 +1,8 @@
-use super::{random_color, run_multiple_times, test_setup_text_buffer, test_setup_text_buffer_with_terminal};
+use super::{random_color, random_narrow_char, run_multiple_times, test_row_text, test_setup_open_terminal, test_setup_text_buffer, test_setup_text_buffer_with_terminal};
+use font::{Font, GridMapping};
 use rand;
+use text_buffer::{BlitOptions, BoxStyle, Color, CursorBoundsPolicy, CursorStyle, OverflowMode, TextAttributes, TextBuffer};
+use terminal::FontHandle;
 use rand::distributions::{Range, Sample};
 
 #[test]
@@ -12,14 +15,39 @@ fn test_text_buffer_aspect_ratio() {
         let height = range.sample(&mut rnd);
 
         let (text_buffer, terminal) = test_setup_text_buffer_with_terminal((width, height));
-        let ar_height = height * terminal.font.line_height as i32;
-        let ar_width = width * terminal.font.size as i32;
+        let ar_height = height * terminal.get_font().line_height as i32;
+        let ar_width = width * terminal.get_font().size as i32;
         let ar = ar_width as f32 / ar_height as f32;
 
         assert_eq!(text_buffer.aspect_ratio, ar);
     });
 }
 
+#[test]
+fn test_text_buffer_with_font_handle() {
+    let terminal = test_setup_open_terminal();
+    let font = Font::from_grid("fonts/iosevka.png", (24, 23), GridMapping::Cp437).unwrap();
+    let handle = terminal.add_font(font);
+    assert_ne!(handle, FontHandle::default());
+    assert_eq!(terminal.get_font_by_handle(handle).size, 24);
+
+    let text_buffer = TextBuffer::new_with_font(&terminal, (10, 10), handle).unwrap();
+    assert_eq!(text_buffer.get_font_handle(), handle);
+    assert_eq!(text_buffer.aspect_ratio, 24.0 / 23.0);
+
+    let text_buffer = TextBuffer::new(&terminal, (10, 10)).unwrap();
+    assert_eq!(text_buffer.get_font_handle(), FontHandle::default());
+}
+
+#[test]
+fn test_text_buffer_with_unregistered_font_handle() {
+    let terminal = test_setup_open_terminal();
+    let other_terminal = test_setup_open_terminal();
+    other_terminal.add_font(Font::load("fonts/iosevka.sfl"));
+    let handle = other_terminal.add_font(Font::load("fonts/iosevka.sfl"));
+    assert!(TextBuffer::new_with_font(&terminal, (10, 10), handle).is_err());
+}
+
 #[test]
 fn test_text_buffer_size() {
     run_multiple_times(10, || {
@@ -49,7 +77,7 @@ fn test_text_buffer_chars_get_char_empty() {
 #[test]
 fn test_text_buffer_chars_put_single_character() {
     run_multiple_times(10, || {
-        let character = rand::random::<char>();
+        let character = random_narrow_char();
         let mut text_buffer = test_setup_text_buffer((2, 2));
         text_buffer.put_char(character);
 
@@ -63,7 +91,7 @@ fn test_text_buffer_chars_write_three_characters() {
     run_multiple_times(10, || {
         let mut text = String::new();
         for _ in 0..3 {
-            text.push(rand::random::<char>());
+            text.push(random_narrow_char());
         }
 
         let mut text_buffer = test_setup_text_buffer((2, 2));
@@ -158,3 +186,547 @@ fn test_text_buffer_cursor_styles() {
         assert_eq!(text_buffer.get_cursor_shakiness(), shakiness);
     });
 }
+
+#[test]
+fn test_text_buffer_control_characters() {
+    let mut text_buffer = test_setup_text_buffer((8, 3));
+    text_buffer.write("ab\ncd\rx\ty");
+    assert_eq!(test_row_text(&text_buffer, 0), "ab      ");
+    assert_eq!(test_row_text(&text_buffer, 1), "xd  y   ");
+    assert_eq!(text_buffer.get_cursor_position(), (5, 1));
+
+    text_buffer.write("\u{8}\u{8}");
+    assert_eq!(test_row_text(&text_buffer, 1), "xd      ");
+    assert_eq!(text_buffer.get_cursor_position(), (3, 1));
+
+    text_buffer.move_cursor(0, 1);
+    text_buffer.put_char('\u{8}');
+    assert_eq!(test_row_text(&text_buffer, 0), "ab      ");
+    assert_eq!(text_buffer.get_cursor_position(), (7, 0));
+}
+
+#[test]
+fn test_text_buffer_overflow_wrap() {
+    let mut text_buffer = test_setup_text_buffer((2, 2));
+    assert_eq!(text_buffer.get_overflow_mode(), OverflowMode::Wrap);
+    text_buffer.write("abcde");
+    assert_eq!(test_row_text(&text_buffer, 0), "eb");
+    assert_eq!(test_row_text(&text_buffer, 1), "cd");
+    assert_eq!(text_buffer.get_cursor_position(), (1, 0));
+}
+
+#[test]
+fn test_text_buffer_overflow_scroll() {
+    let mut text_buffer = test_setup_text_buffer((2, 2));
+    text_buffer.set_overflow_mode(OverflowMode::Scroll);
+    text_buffer.write("abcde");
+    assert_eq!(test_row_text(&text_buffer, 0), "cd");
+    assert_eq!(test_row_text(&text_buffer, 1), "e ");
+    assert_eq!(text_buffer.get_cursor_position(), (1, 1));
+
+    text_buffer.write("\n");
+    assert_eq!(test_row_text(&text_buffer, 0), "e ");
+    assert_eq!(test_row_text(&text_buffer, 1), "  ");
+    assert_eq!(text_buffer.get_cursor_position(), (0, 1));
+}
+
+#[test]
+fn test_text_buffer_overflow_clip() {
+    let mut text_buffer = test_setup_text_buffer((2, 2));
+    text_buffer.set_overflow_mode(OverflowMode::Clip);
+    text_buffer.write("abcde\nf");
+    assert_eq!(test_row_text(&text_buffer, 0), "ab");
+    assert_eq!(test_row_text(&text_buffer, 1), "cd");
+    assert_eq!(text_buffer.get_cursor_position(), (1, 1));
+
+    text_buffer.put_char('\u{8}');
+    assert_eq!(test_row_text(&text_buffer, 1), "c ");
+
+    text_buffer.write("xy");
+    assert_eq!(test_row_text(&text_buffer, 1), "cx");
+    text_buffer.move_cursor(0, 0);
+    text_buffer.put_char('z');
+    assert_eq!(test_row_text(&text_buffer, 0), "zb");
+}
+
+#[test]
+fn test_text_buffer_scrollback() {
+    let mut text_buffer = test_setup_text_buffer((2, 2));
+    text_buffer.set_overflow_mode(OverflowMode::Scroll);
+    text_buffer.set_scrollback_limit(3);
+    text_buffer.write("0\n1\n2\n3\n4\n5");
+    assert_eq!(text_buffer.get_scrollback_len(), 3);
+    assert_eq!(test_row_text(&text_buffer, 0), "4 ");
+    assert_eq!(test_row_text(&text_buffer, 1), "5 ");
+
+    text_buffer.scroll_view_up(1);
+    assert_eq!(text_buffer.get_scroll_offset(), 1);
+    assert_eq!(text_buffer.get_visible_character(0, 0).get_char(), '3');
+    assert_eq!(text_buffer.get_visible_character(0, 1).get_char(), '4');
+
+    // The oldest rows were dropped, so the view can't go further up than row '1'
+    text_buffer.scroll_view_page_up();
+    text_buffer.scroll_view_page_up();
+    assert_eq!(text_buffer.get_scroll_offset(), 3);
+    assert_eq!(text_buffer.get_visible_character(0, 0).get_char(), '1');
+
+    // New rows keep the view in place
+    text_buffer.set_scrollback_limit(10);
+    text_buffer.write("\n6");
+    assert_eq!(text_buffer.get_scroll_offset(), 4);
+    assert_eq!(text_buffer.get_visible_character(0, 0).get_char(), '1');
+
+    text_buffer.scroll_view_page_down();
+    assert_eq!(text_buffer.get_visible_character(0, 0).get_char(), '3');
+    text_buffer.scroll_view_to_bottom();
+    assert_eq!(text_buffer.get_visible_character(0, 0).get_char(), '5');
+    assert_eq!(text_buffer.get_visible_character(0, 1).get_char(), '6');
+
+    text_buffer.clear_scrollback();
+    assert_eq!(text_buffer.get_scrollback_len(), 0);
+}
+
+#[test]
+fn test_text_buffer_scrollback_disabled_by_default() {
+    let mut text_buffer = test_setup_text_buffer((2, 2));
+    text_buffer.set_overflow_mode(OverflowMode::Scroll);
+    text_buffer.write("0\n1\n2\n3");
+    assert_eq!(text_buffer.get_scrollback_len(), 0);
+    text_buffer.scroll_view_up(1);
+    assert_eq!(text_buffer.get_scroll_offset(), 0);
+}
+
+#[test]
+fn test_text_buffer_resize() {
+    let (mut text_buffer, terminal) = test_setup_text_buffer_with_terminal((3, 2));
+    text_buffer.write("abcdef");
+    text_buffer.move_cursor(2, 1);
+
+    text_buffer.resize(&terminal, (2, 3)).unwrap();
+    assert_eq!(text_buffer.chars.len(), 6);
+    assert_eq!(test_row_text(&text_buffer, 0), "ab");
+    assert_eq!(test_row_text(&text_buffer, 1), "de");
+    assert_eq!(test_row_text(&text_buffer, 2), "  ");
+    assert_eq!(text_buffer.get_cursor_position(), (1, 1));
+
+    let font = terminal.get_font();
+    let aspect_ratio = (2 * font.size) as f32 / (3 * font.line_height) as f32;
+    assert_eq!(text_buffer.aspect_ratio, aspect_ratio);
+
+    // A clipped cursor that is moved inside the new dimensions can write again
+    text_buffer.set_overflow_mode(OverflowMode::Clip);
+    text_buffer.write("ghijkl");
+    text_buffer.resize(&terminal, (2, 2)).unwrap();
+    text_buffer.write("x");
+    assert_eq!(test_row_text(&text_buffer, 1), "dx");
+}
+
+#[test]
+fn test_text_buffer_resize_scrollback_and_invalid_dimensions() {
+    let (mut text_buffer, terminal) = test_setup_text_buffer_with_terminal((3, 1));
+    text_buffer.set_overflow_mode(OverflowMode::Scroll);
+    text_buffer.set_scrollback_limit(5);
+    text_buffer.write("abc");
+
+    text_buffer.resize(&terminal, (4, 1)).unwrap();
+    text_buffer.scroll_view_up(1);
+    assert_eq!(text_buffer.get_visible_character(2, 0).get_char(), 'c');
+    assert_eq!(text_buffer.get_visible_character(3, 0).get_char(), ' ');
+
+    assert!(text_buffer.resize(&terminal, (0, 1)).is_err());
+    assert_eq!(text_buffer.width, 4);
+}
+
+#[test]
+fn test_text_buffer_blit() {
+    let mut source = test_setup_text_buffer((3, 2));
+    source.change_cursor_fg_color([1.0, 0.5, 0.0, 1.0]);
+    source.write("abc de");
+    let mut text_buffer = test_setup_text_buffer((4, 3));
+    text_buffer.write("xxxxxxxxxxxx");
+
+    text_buffer.blit(&source, (1, 0), (3, 3), (2, 1), &BlitOptions::new());
+    assert_eq!(test_row_text(&text_buffer, 0), "xxxx");
+    assert_eq!(test_row_text(&text_buffer, 1), "xxbc");
+    assert_eq!(test_row_text(&text_buffer, 2), "xxde");
+    assert_eq!(
+        text_buffer.get_character(2, 1).get_fg_color(),
+        [1.0, 0.5, 0.0, 1.0]
+    );
+
+    let options = BlitOptions::new()
+        .with_skip_transparent(true)
+        .with_tint([0.5, 0.5, 0.5, 1.0]);
+    text_buffer.blit(&source, (0, 0), (3, 2), (0, 0), &options);
+    assert_eq!(test_row_text(&text_buffer, 0), "abcx");
+    assert_eq!(test_row_text(&text_buffer, 1), "xdec");
+    assert_eq!(
+        text_buffer.get_character(0, 0).get_fg_color(),
+        [0.5, 0.25, 0.0, 1.0]
+    );
+}
+
+#[test]
+fn test_text_buffer_blit_alpha() {
+    let mut source = test_setup_text_buffer((2, 1));
+    source.change_cursor_bg_color([1.0, 1.0, 1.0, 1.0]);
+    source.write("a ");
+    let mut text_buffer = test_setup_text_buffer((2, 1));
+    text_buffer.change_cursor_bg_color([0.0, 0.0, 0.0, 1.0]);
+    text_buffer.write("xy");
+
+    let options = BlitOptions::new().with_alpha(0.5, 0.25);
+    text_buffer.blit(&source, (0, 0), (2, 1), (0, 0), &options);
+    assert_eq!(test_row_text(&text_buffer, 0), "ay");
+    assert_eq!(text_buffer.get_character(0, 0).get_fg_color(), [1.0, 1.0, 1.0, 0.5]);
+    assert_eq!(
+        text_buffer.get_character(1, 0).get_bg_color(),
+        [0.25, 0.25, 0.25, 1.0]
+    );
+}
+
+#[test]
+fn test_text_buffer_blit_wide_characters() {
+    let mut source = test_setup_text_buffer((4, 1));
+    source.write("日本");
+    let mut text_buffer = test_setup_text_buffer((4, 1));
+    text_buffer.write("xxxx");
+
+    // The source rectangle starts in the middle of the first character
+    text_buffer.blit(&source, (1, 0), (3, 1), (0, 0), &BlitOptions::new());
+    assert_eq!(test_row_text(&text_buffer, 0), " 本 x");
+    assert_eq!(text_buffer.get_character(0, 0).get_width(), 1);
+    assert_eq!(text_buffer.get_character(1, 0).get_width(), 2);
+
+    // The destination rectangle starts in the middle of a character
+    text_buffer.blit(&source, (0, 0), (1, 1), (2, 0), &BlitOptions::new());
+    assert_eq!(test_row_text(&text_buffer, 0), "   x");
+    assert_eq!(text_buffer.get_character(1, 0).get_width(), 1);
+    assert_eq!(text_buffer.get_character(2, 0).get_width(), 1);
+
+    // The continuations are copied along with their characters, even when skipping transparent characters
+    let options = BlitOptions::new().with_skip_transparent(true);
+    text_buffer.blit(&source, (0, 0), (2, 1), (1, 0), &options);
+    assert_eq!(text_buffer.get_character(1, 0).get_char(), '日');
+    assert_eq!(text_buffer.get_character(2, 0).get_width(), 0);
+}
+
+#[test]
+fn test_text_buffer_draw_box_junctions() {
+    let mut text_buffer = test_setup_text_buffer((5, 4));
+    text_buffer.draw_box((0, 0), (5, 4), BoxStyle::Single);
+    text_buffer.draw_vertical_line((2, 0), 4, BoxStyle::Double);
+    text_buffer.draw_horizontal_line((2, 2), 3, BoxStyle::Single);
+    assert_eq!(test_row_text(&text_buffer, 0), "┌─╥─┐");
+    assert_eq!(test_row_text(&text_buffer, 1), "│ ║ │");
+    assert_eq!(test_row_text(&text_buffer, 2), "│ ╟─┤");
+    assert_eq!(test_row_text(&text_buffer, 3), "└─╨─┘");
+
+    text_buffer.clear();
+    text_buffer.draw_box((0, 0), (3, 2), BoxStyle::Rounded);
+    text_buffer.draw_box((2, 1), (3, 3), BoxStyle::Double);
+    assert_eq!(test_row_text(&text_buffer, 0), "╭─╮  ");
+    assert_eq!(test_row_text(&text_buffer, 1), "╰─╬═╗");
+    assert_eq!(test_row_text(&text_buffer, 2), "  ║ ║");
+    assert_eq!(test_row_text(&text_buffer, 3), "  ╚═╝");
+}
+
+#[test]
+fn test_text_buffer_draw_lines_and_rects() {
+    let mut text_buffer = test_setup_text_buffer((5, 3));
+    text_buffer.change_cursor_fg_color([1.0, 0.0, 0.0, 1.0]);
+    text_buffer.draw_line((0, 0), (4, 2), '*');
+    assert_eq!(test_row_text(&text_buffer, 0), "*    ");
+    assert_eq!(test_row_text(&text_buffer, 1), " **  ");
+    assert_eq!(test_row_text(&text_buffer, 2), "   **");
+    assert_eq!(
+        text_buffer.get_character(2, 1).get_fg_color(),
+        [1.0, 0.0, 0.0, 1.0]
+    );
+
+    text_buffer.clear();
+    text_buffer.draw_rect((1, 0), (4, 3), '#');
+    text_buffer.fill_rect((2, 1), (5, 5), '.');
+    assert_eq!(test_row_text(&text_buffer, 0), " ####");
+    assert_eq!(test_row_text(&text_buffer, 1), " #...");
+    assert_eq!(test_row_text(&text_buffer, 2), " #...");
+}
+
+#[test]
+fn test_text_buffer_draw_far_outside() {
+    let mut text_buffer = test_setup_text_buffer((5, 3));
+    text_buffer.draw_line((i32::MIN, 1), (i32::MAX, 1), '-');
+    assert_eq!(test_row_text(&text_buffer, 1), "-----");
+    text_buffer.draw_line((-1_000_000_000, -1_000_000_000), (i32::MAX, i32::MAX), '/');
+    assert_eq!(test_row_text(&text_buffer, 0), "/    ");
+    assert_eq!(test_row_text(&text_buffer, 1), "-/---");
+    assert_eq!(test_row_text(&text_buffer, 2), "  /  ");
+
+    text_buffer.fill_rect((-10, 2), (i32::MAX, i32::MAX), '.');
+    assert_eq!(test_row_text(&text_buffer, 2), ".....");
+    text_buffer.draw_rect((-5, 0), (i32::MAX, i32::MAX), '#');
+    assert_eq!(test_row_text(&text_buffer, 0), "#####");
+
+    text_buffer.fill_circle((2, 1), i32::MAX, 'o');
+    assert_eq!(test_row_text(&text_buffer, 1), "ooooo");
+}
+
+#[test]
+fn test_text_buffer_draw_huge_lines_and_circles() {
+    let mut text_buffer = test_setup_text_buffer((5, 3));
+    text_buffer.draw_horizontal_line((-3, 0), i32::MAX, BoxStyle::Single);
+    text_buffer.draw_horizontal_line((i32::MAX, 2), i32::MAX, BoxStyle::Single);
+    text_buffer.draw_vertical_line((i32::MIN, 0), i32::MAX, BoxStyle::Single);
+    assert_eq!(test_row_text(&text_buffer, 0), "─────");
+    assert_eq!(test_row_text(&text_buffer, 2), "     ");
+
+    text_buffer.clear();
+    text_buffer.draw_vertical_line((1, -2), i32::MAX, BoxStyle::Double);
+    text_buffer.draw_vertical_line((3, i32::MIN), i32::MAX, BoxStyle::Double);
+    assert_eq!(test_row_text(&text_buffer, 0), " ║   ");
+    assert_eq!(test_row_text(&text_buffer, 2), " ║   ");
+
+    // Only the part of the outline that crosses the text buffer is drawn
+    text_buffer.clear();
+    text_buffer.draw_circle((2, 1), i32::MAX, 'o');
+    text_buffer.draw_circle((i32::MAX, i32::MIN), i32::MAX, 'o');
+    text_buffer.draw_circle((-1_000_000, 1), 1_000_002, '#');
+    assert_eq!(test_row_text(&text_buffer, 0), "  #  ");
+    assert_eq!(test_row_text(&text_buffer, 1), "  #  ");
+    assert_eq!(test_row_text(&text_buffer, 2), "  #  ");
+}
+
+#[test]
+fn test_text_buffer_circles_and_flood_fill() {
+    let mut text_buffer = test_setup_text_buffer((7, 7));
+    text_buffer.draw_circle((3, 3), 3, '#');
+    assert_eq!(test_row_text(&text_buffer, 0), "  ###  ");
+    assert_eq!(test_row_text(&text_buffer, 3), "#     #");
+
+    text_buffer.flood_fill((3, 3), '.');
+    assert_eq!(test_row_text(&text_buffer, 3), "#.....#");
+    assert_eq!(test_row_text(&text_buffer, 0), "  ###  ");
+    assert_eq!(text_buffer.get_character(0, 0).get_char(), ' ');
+
+    text_buffer.clear();
+    text_buffer.fill_circle((3, 3), 1, 'o');
+    assert_eq!(test_row_text(&text_buffer, 2), "  ooo  ");
+    assert_eq!(test_row_text(&text_buffer, 3), "  ooo  ");
+    assert_eq!(test_row_text(&text_buffer, 1), "       ");
+}
+
+#[test]
+fn test_text_buffer_attributes() {
+    let mut text_buffer = test_setup_text_buffer((4, 2));
+    let attributes = TextAttributes::BOLD | TextAttributes::UNDERLINE;
+    text_buffer.change_cursor_attributes(attributes);
+    assert_eq!(text_buffer.get_cursor_attributes(), attributes);
+    text_buffer.write("ab");
+    text_buffer.change_cursor_attributes(TextAttributes::NONE);
+    text_buffer.write("c");
+    text_buffer.draw_line((0, 1), (1, 1), '-');
+
+    let character = text_buffer.get_character(1, 0);
+    assert!(character.get_attributes().contains(TextAttributes::BOLD));
+    assert!(character.get_attributes().contains(attributes));
+    assert!(!character.get_attributes().contains(TextAttributes::ITALIC));
+    assert!(text_buffer.get_character(2, 0).get_attributes().is_empty());
+    assert!(text_buffer.get_character(0, 1).get_attributes().is_empty());
+
+    let mut attributes = TextAttributes::NONE;
+    attributes |= TextAttributes::ITALIC | TextAttributes::BLINK;
+    attributes.remove(TextAttributes::ITALIC);
+    assert_eq!(attributes, TextAttributes::BLINK);
+}
+
+#[test]
+fn test_text_buffer_reverse_and_dim_colors() {
+    let mut text_buffer = test_setup_text_buffer((3, 1));
+    text_buffer.change_cursor_fg_color([1.0, 0.5, 0.0, 1.0]);
+    text_buffer.change_cursor_bg_color([0.0, 0.0, 1.0, 1.0]);
+    text_buffer.change_cursor_attributes(TextAttributes::REVERSE);
+    text_buffer.put_char('a');
+    text_buffer.change_cursor_attributes(TextAttributes::DIM);
+    text_buffer.put_char('b');
+
+    assert_eq!(
+        text_buffer.get_character(0, 0).get_drawn_colors(),
+        (Color::new(0.0, 0.0, 1.0, 1.0), Color::new(1.0, 0.5, 0.0, 1.0))
+    );
+    assert_eq!(
+        text_buffer.get_character(1, 0).get_drawn_colors(),
+        (Color::new(0.5, 0.25, 0.0, 1.0), Color::new(0.0, 0.0, 1.0, 1.0))
+    );
+    // The stored colors are left as they are
+    assert_eq!(
+        text_buffer.get_character(0, 0).get_fg_color(),
+        [1.0, 0.5, 0.0, 1.0]
+    );
+
+    // Reversing the default transparent background gives black text instead of invisible text
+    text_buffer.change_cursor_bg_color(Color::TRANSPARENT);
+    text_buffer.change_cursor_attributes(TextAttributes::REVERSE);
+    text_buffer.write("c");
+    assert_eq!(
+        text_buffer.get_character(2, 0).get_drawn_colors(),
+        (Color::BLACK, Color::new(1.0, 0.5, 0.0, 1.0))
+    );
+}
+
+#[test]
+fn test_text_buffer_wide_characters() {
+    let mut text_buffer = test_setup_text_buffer((3, 2));
+    text_buffer.write("日a本");
+
+    let character = text_buffer.get_character(0, 0);
+    assert_eq!(character.get_char(), '日');
+    assert_eq!(character.get_width(), 2);
+    assert_eq!(text_buffer.get_character(1, 0).get_width(), 0);
+    assert_eq!(text_buffer.get_character(2, 0).get_char(), 'a');
+    // The second wide character doesn't fit on the first row
+    assert_eq!(text_buffer.get_character(0, 1).get_char(), '本');
+    assert_eq!(text_buffer.get_character(1, 1).get_width(), 0);
+
+    // Replacing the continuation empties the wide character too
+    text_buffer.move_cursor(1, 0);
+    text_buffer.put_char('b');
+    assert_eq!(text_buffer.get_character(0, 0).get_char(), ' ');
+    assert_eq!(text_buffer.get_character(0, 0).get_width(), 1);
+    assert_eq!(text_buffer.get_character(1, 0).get_char(), 'b');
+
+    // Backspace erases the whole wide character
+    text_buffer.move_cursor(2, 1);
+    text_buffer.put_char('\u{8}');
+    assert_eq!(text_buffer.get_cursor_position(), (0, 1));
+    assert_eq!(text_buffer.get_character(0, 1).get_char(), ' ');
+    assert_eq!(text_buffer.get_character(1, 1).get_width(), 1);
+}
+
+#[test]
+fn test_text_buffer_combining_characters() {
+    let mut text_buffer = test_setup_text_buffer((3, 1));
+    text_buffer.write("e\u{301}a\u{308}\u{304}");
+
+    let character = text_buffer.get_character(0, 0);
+    assert_eq!(character.get_char(), 'e');
+    assert_eq!(character.get_grapheme(), "e\u{301}");
+    assert_eq!(text_buffer.get_character(1, 0).get_grapheme(), "a\u{308}\u{304}");
+    assert_eq!(text_buffer.get_cursor_position(), (2, 0));
+
+    // Writing over the character removes the marks
+    text_buffer.move_cursor(0, 0);
+    text_buffer.put_char('o');
+    assert_eq!(text_buffer.get_character(0, 0).get_grapheme(), "o");
+}
+
+#[test]
+fn test_text_buffer_control_characters_take_a_cell() {
+    let mut text_buffer = test_setup_text_buffer((3, 1));
+    // CP437 grid fonts have a glyph for '\0', so it isn't attached to the previous character like a combining mark
+    text_buffer.write("a\0\u{1}");
+    assert_eq!(text_buffer.get_character(0, 0).get_grapheme(), "a");
+    assert_eq!(text_buffer.get_character(1, 0).get_char(), '\0');
+    assert_eq!(text_buffer.get_character(1, 0).get_width(), 1);
+    assert_eq!(text_buffer.get_character(2, 0).get_char(), '\u{1}');
+}
+
+#[test]
+fn test_text_buffer_cursor_rect() {
+    let mut text_buffer = test_setup_text_buffer((4, 2));
+    text_buffer.write("a");
+    assert!(!text_buffer.is_cursor_visible());
+    assert_eq!(text_buffer.get_cursor_rect(), None);
+
+    text_buffer.set_cursor_visible(true);
+    assert_eq!(text_buffer.get_cursor_style(), CursorStyle::Block);
+    assert_eq!(text_buffer.get_cursor_rect(), Some((0.25, 0.0, 0.25, 0.5)));
+
+    text_buffer.set_cursor_style(CursorStyle::Bar);
+    text_buffer.move_cursor(2, 1);
+    let (x, y, width, height) = text_buffer.get_cursor_rect().unwrap();
+    assert_eq!((x, y, height), (0.5, 0.5, 0.5));
+    assert!(width < 0.25);
+
+    // The block cursor covers both cells of a wide character
+    text_buffer.set_cursor_style(CursorStyle::Block);
+    text_buffer.write("日");
+    text_buffer.move_cursor(2, 1);
+    assert_eq!(text_buffer.get_cursor_rect(), Some((0.5, 0.5, 0.5, 0.5)));
+
+    text_buffer.set_cursor_blink_rate(-1.0);
+    assert_eq!(text_buffer.get_cursor_blink_rate(), 0.0);
+}
+
+#[test]
+fn test_text_buffer_cursor_bounds_policy() {
+    let mut text_buffer = test_setup_text_buffer((4, 3));
+    text_buffer.move_cursor(1, 1);
+    assert_eq!(text_buffer.get_cursor_bounds_policy(), CursorBoundsPolicy::Ignore);
+    text_buffer.move_cursor_relative(3, 0);
+    assert_eq!(text_buffer.get_cursor_position(), (1, 1));
+    text_buffer.move_cursor_relative(2, 1);
+    assert_eq!(text_buffer.get_cursor_position(), (3, 2));
+
+    text_buffer.set_cursor_bounds_policy(CursorBoundsPolicy::Clamp);
+    text_buffer.move_cursor(-5, 10);
+    assert_eq!(text_buffer.get_cursor_position(), (0, 2));
+
+    text_buffer.set_cursor_bounds_policy(CursorBoundsPolicy::Error);
+    assert!(text_buffer.try_move_cursor(4, 0).is_err());
+    assert!(text_buffer.try_move_cursor_relative(0, 1).is_err());
+    assert_eq!(text_buffer.get_cursor_position(), (0, 2));
+    assert!(text_buffer.try_move_cursor_relative(1, -2).is_ok());
+    assert_eq!(text_buffer.get_cursor_position(), (1, 0));
+}
+
+#[test]
+#[should_panic]
+fn test_text_buffer_cursor_bounds_policy_error_panics() {
+    let mut text_buffer = test_setup_text_buffer((4, 3));
+    text_buffer.set_cursor_bounds_policy(CursorBoundsPolicy::Error);
+    text_buffer.move_cursor(0, 3);
+}
+
+#[test]
+fn test_text_buffer_cursor_line_start_and_end() {
+    let mut text_buffer = test_setup_text_buffer((5, 2));
+    text_buffer.write("ab");
+    text_buffer.move_cursor_to_line_start();
+    assert_eq!(text_buffer.get_cursor_position(), (0, 0));
+    text_buffer.move_cursor_to_line_end();
+    assert_eq!(text_buffer.get_cursor_position(), (2, 0));
+
+    text_buffer.move_cursor(0, 1);
+    text_buffer.write("abcde");
+    text_buffer.move_cursor(1, 1);
+    text_buffer.move_cursor_to_line_end();
+    assert_eq!(text_buffer.get_cursor_position(), (4, 1));
+}
+
+#[test]
+fn test_text_buffer_cursor_stack() {
+    let mut text_buffer = test_setup_text_buffer((4, 3));
+    assert!(!text_buffer.pop_cursor());
+
+    text_buffer.move_cursor(3, 2);
+    text_buffer.change_cursor_shakiness(0.5);
+    text_buffer.push_cursor();
+    text_buffer.move_cursor(1, 1);
+    text_buffer.change_cursor_bg_color([1.0; 4]);
+    text_buffer.push_cursor();
+    text_buffer.move_cursor(0, 0);
+    text_buffer.change_cursor_shakiness(0.0);
+    assert_eq!(text_buffer.get_cursor_stack_len(), 2);
+
+    assert!(text_buffer.pop_cursor());
+    assert_eq!(text_buffer.get_cursor_position(), (1, 1));
+    assert_eq!(text_buffer.get_cursor_bg_color(), [1.0; 4]);
+    assert_eq!(text_buffer.get_cursor_shakiness(), 0.5);
+
+    let terminal = test_setup_open_terminal();
+    text_buffer.resize(&terminal, (2, 2)).unwrap();
+    assert!(text_buffer.pop_cursor());
+    assert_eq!(text_buffer.get_cursor_position(), (1, 1));
+    assert_eq!(text_buffer.get_cursor_bg_color(), [0.0; 4]);
+    assert_eq!(text_buffer.get_cursor_stack_len(), 0);
+}
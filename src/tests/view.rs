@@ -0,0 +1,138 @@
+use super::{test_row_text, test_setup_text_buffer};
+use text_buffer::parser::Parser;
+use text_buffer::{OverflowMode, TextAttributes};
+
+#[test]
+fn test_view_write_clips_to_rectangle() {
+    let mut text_buffer = test_setup_text_buffer((6, 4));
+    text_buffer.write("xxxxxxxxxxxxxxxxxxxxxxxx");
+    {
+        let mut view = text_buffer.view((1, 1), (3, 2));
+        view.clear();
+        view.write("abcefghij");
+        assert_eq!(view.get_cursor_position(), (2, 1));
+        assert_eq!(view.get_character(0, 1).unwrap().get_char(), 'e');
+        assert!(view.get_character(3, 0).is_none());
+    }
+    assert_eq!(test_row_text(&text_buffer, 0), "xxxxxx");
+    assert_eq!(test_row_text(&text_buffer, 1), "xabcxx");
+    assert_eq!(test_row_text(&text_buffer, 2), "xefgxx");
+    assert_eq!(test_row_text(&text_buffer, 3), "xxxxxx");
+    assert_eq!(text_buffer.get_cursor_position(), (0, 0));
+}
+
+#[test]
+fn test_view_outside_text_buffer_and_nested() {
+    let mut text_buffer = test_setup_text_buffer((4, 3));
+    {
+        let mut view = text_buffer.view((-1, 1), (6, 2));
+        view.write("abcdef");
+        {
+            let mut nested = view.view((4, 0), (4, 4));
+            nested.write("123456");
+            assert_eq!(nested.get_position(), (3, 1));
+        }
+    }
+    assert_eq!(test_row_text(&text_buffer, 0), "    ");
+    assert_eq!(test_row_text(&text_buffer, 1), "bcd1");
+    assert_eq!(test_row_text(&text_buffer, 2), "   5");
+}
+
+#[test]
+fn test_view_overflow_modes() {
+    let mut text_buffer = test_setup_text_buffer((4, 4));
+    {
+        let mut view = text_buffer.view((0, 1), (2, 2));
+        view.write("abcde");
+        assert_eq!(view.get_cursor_position(), (1, 1));
+
+        view.set_overflow_mode(OverflowMode::Scroll);
+        view.move_cursor(0, 1);
+        view.write("fgh");
+    }
+    assert_eq!(test_row_text(&text_buffer, 0), "    ");
+    assert_eq!(test_row_text(&text_buffer, 1), "fg  ");
+    assert_eq!(test_row_text(&text_buffer, 2), "h   ");
+    assert_eq!(test_row_text(&text_buffer, 3), "    ");
+}
+
+#[test]
+fn test_view_wide_characters() {
+    let mut text_buffer = test_setup_text_buffer((6, 2));
+    {
+        let mut view = text_buffer.view((1, 0), (3, 2));
+        view.write("日本e\u{301}");
+        assert_eq!(view.get_cursor_position(), (2, 1));
+        assert_eq!(view.get_character(0, 0).unwrap().get_width(), 2);
+        assert_eq!(view.get_character(1, 0).unwrap().get_width(), 0);
+    }
+    assert_eq!(test_row_text(&text_buffer, 0), " 日    ");
+    assert_eq!(test_row_text(&text_buffer, 1), " 本 e  ");
+    assert_eq!(text_buffer.get_character(3, 1).get_grapheme(), "e\u{301}");
+
+    // Only half of the first character would be inside the text buffer
+    {
+        let mut view = text_buffer.view((-1, 0), (5, 1));
+        view.write("日本");
+    }
+    assert_eq!(test_row_text(&text_buffer, 0), " 本    ");
+    assert_eq!(text_buffer.get_character(0, 0).get_width(), 1);
+    assert_eq!(text_buffer.get_character(1, 0).get_width(), 2);
+    assert_eq!(text_buffer.get_character(2, 0).get_width(), 0);
+}
+
+#[test]
+fn test_view_scroll_splits_wide_characters_on_the_sides() {
+    let mut text_buffer = test_setup_text_buffer((4, 2));
+    text_buffer.write("漢字ab");
+    {
+        let mut view = text_buffer.view((1, 0), (2, 2));
+        view.set_overflow_mode(OverflowMode::Scroll);
+        view.move_cursor(0, 1);
+        view.write("\n");
+    }
+    assert_eq!(test_row_text(&text_buffer, 0), " b  ");
+    assert_eq!(test_row_text(&text_buffer, 1), "a   ");
+    assert_eq!(text_buffer.get_character(0, 0).get_width(), 1);
+    assert_eq!(text_buffer.get_character(3, 0).get_width(), 1);
+}
+
+#[test]
+fn test_view_parser() {
+    let mut text_buffer = test_setup_text_buffer((4, 2));
+    let mut parser = Parser::new();
+    parser.add_color("red", [1.0, 0.0, 0.0, 1.0]);
+    {
+        let mut view = text_buffer.view((2, 0), (2, 1));
+        parser.write_to_view(&mut view, "[fg=red]ab[/fg]cd");
+        assert_eq!(view.get_cursor_fg_color(), [1.0; 4]);
+    }
+    assert_eq!(test_row_text(&text_buffer, 0), "  ab");
+    assert_eq!(test_row_text(&text_buffer, 1), "    ");
+    assert_eq!(
+        text_buffer.get_character(3, 0).get_fg_color(),
+        [1.0, 0.0, 0.0, 1.0]
+    );
+}
+
+#[test]
+fn test_view_cursor_attributes() {
+    let mut text_buffer = test_setup_text_buffer((4, 1));
+    text_buffer.change_cursor_attributes(TextAttributes::ITALIC);
+    {
+        let mut view = text_buffer.view((1, 0), (3, 1));
+        assert_eq!(view.get_cursor_attributes(), TextAttributes::ITALIC);
+        view.put_char('a');
+        view.change_cursor_attributes(TextAttributes::REVERSE);
+        view.put_char('b');
+    }
+    assert_eq!(
+        text_buffer.get_character(1, 0).get_attributes(),
+        TextAttributes::ITALIC
+    );
+    assert_eq!(
+        text_buffer.get_character(2, 0).get_attributes(),
+        TextAttributes::REVERSE
+    );
+    assert_eq!(text_buffer.get_cursor_attributes(), TextAttributes::ITALIC);
+}
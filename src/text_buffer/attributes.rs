@@ -0,0 +1,106 @@
+//! Text attributes, like bold and underline, of the characters in a `TextBuffer`.
+
+use std::ops::{BitOr, BitOrAssign};
+
+use super::{Color, TermCharacter};
+
+/// A set of text attributes of a [`TermCharacter`](struct.TermCharacter.html), combined with `|`. Example:
+///
+/// ```
+/// use glerminal::terminal::TerminalBuilder;
+/// use glerminal::text_buffer::{TextAttributes, TextBuffer};
+///
+/// let terminal = TerminalBuilder::new().with_headless(true).build();
+/// let mut text_buffer = TextBuffer::new(&terminal, (80, 24)).unwrap();
+///
+/// text_buffer.change_cursor_attributes(TextAttributes::BOLD | TextAttributes::UNDERLINE);
+/// text_buffer.write("Important!");
+/// assert!(text_buffer.get_character(0, 0).get_attributes().contains(TextAttributes::BOLD));
+/// ```
+///
+/// The attributes are synthesized when drawing: bold characters are drawn twice with a small offset, italic characters
+/// are skewed, underlines and strikethroughs are drawn as lines with the foreground color, reverse video swaps the
+/// foreground and background colors, dim halves the brightness of the foreground color and blinking characters are
+/// hidden every other half a second.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct TextAttributes(u8);
+
+impl TextAttributes {
+    /// No attributes
+    pub const NONE: TextAttributes = TextAttributes(0);
+    /// Bold (thicker) characters
+    pub const BOLD: TextAttributes = TextAttributes(1);
+    /// Dim (darker) characters
+    pub const DIM: TextAttributes = TextAttributes(1 << 1);
+    /// Italic (slanted) characters
+    pub const ITALIC: TextAttributes = TextAttributes(1 << 2);
+    /// A line under the characters
+    pub const UNDERLINE: TextAttributes = TextAttributes(1 << 3);
+    /// A line through the characters
+    pub const STRIKETHROUGH: TextAttributes = TextAttributes(1 << 4);
+    /// The foreground and background colors are swapped; a fully transparent background becomes black text
+    pub const REVERSE: TextAttributes = TextAttributes(1 << 5);
+    /// The characters blink
+    pub const BLINK: TextAttributes = TextAttributes(1 << 6);
+
+    /// Returns whether there are no attributes in the set.
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns whether all of the given attributes are in the set.
+    pub fn contains(&self, attributes: TextAttributes) -> bool {
+        self.0 & attributes.0 == attributes.0
+    }
+
+    /// Adds the given attributes to the set.
+    pub fn insert(&mut self, attributes: TextAttributes) {
+        self.0 |= attributes.0;
+    }
+
+    /// Removes the given attributes from the set.
+    pub fn remove(&mut self, attributes: TextAttributes) {
+        self.0 &= !attributes.0;
+    }
+}
+
+impl BitOr for TextAttributes {
+    type Output = TextAttributes;
+
+    fn bitor(self, other: TextAttributes) -> TextAttributes {
+        TextAttributes(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for TextAttributes {
+    fn bitor_assign(&mut self, other: TextAttributes) {
+        self.insert(other);
+    }
+}
+
+impl TermCharacter {
+    /// Gets the text attributes of the TermCharacter
+    pub fn get_attributes(&self) -> TextAttributes {
+        self.attributes
+    }
+
+    /// Returns the foreground and background colors the character is drawn with, after applying reverse video and dim.
+    pub(crate) fn get_drawn_colors(&self) -> (Color, Color) {
+        let (mut fg_color, bg_color) = if self.attributes.contains(TextAttributes::REVERSE) {
+            let fg_color = if self.bg_color.a == 0.0 {
+                Color::BLACK
+            } else {
+                self.bg_color
+            };
+            (fg_color, self.fg_color)
+        } else {
+            (self.fg_color, self.bg_color)
+        };
+        if self.attributes.contains(TextAttributes::DIM) {
+            fg_color.r *= 0.5;
+            fg_color.g *= 0.5;
+            fg_color.b *= 0.5;
+        }
+        (fg_color, bg_color)
+    }
+}
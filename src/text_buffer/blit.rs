@@ -0,0 +1,154 @@
+//! Copying rectangles of characters between `TextBuffer`s.
+
+use super::{Color, TermCharacter, TextBuffer};
+
+/// Options for [`TextBuffer::blit`](struct.TextBuffer.html#method.blit). By default, characters are copied as they are.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlitOptions {
+    skip_transparent: bool,
+    alpha: Option<(f32, f32)>,
+    tint: Color,
+}
+
+impl Default for BlitOptions {
+    fn default() -> BlitOptions {
+        BlitOptions::new()
+    }
+}
+
+impl BlitOptions {
+    /// Creates new options that copy the characters as they are.
+    pub fn new() -> BlitOptions {
+        BlitOptions {
+            skip_transparent: false,
+            alpha: None,
+            tint: Color::BRIGHT_WHITE,
+        }
+    }
+
+    /// Sets whether transparent characters (spaces with a fully transparent background) are skipped, leaving
+    /// the characters under them as they are (default false).
+    pub fn with_skip_transparent(mut self, skip_transparent: bool) -> BlitOptions {
+        self.skip_transparent = skip_transparent;
+        self
+    }
+
+    /// Blends the copied characters over the characters under them, instead of replacing them.
+    ///
+    /// The background colors are alpha-blended, with the alpha of the copied background multiplied by `bg_alpha`.
+    /// Copied characters other than spaces replace the characters under them, and the alpha of their foreground color
+    /// is multiplied by `fg_alpha`; spaces keep the characters under them. Both are values from 0.0 to 1.0.
+    pub fn with_alpha(mut self, fg_alpha: f32, bg_alpha: f32) -> BlitOptions {
+        self.alpha = Some((fg_alpha, bg_alpha));
+        self
+    }
+
+    /// Sets the color the copied foreground and background colors are multiplied with (default white).
+    pub fn with_tint<T: Into<Color>>(mut self, tint: T) -> BlitOptions {
+        self.tint = tint.into();
+        self
+    }
+
+    /// Returns the character resulting from putting the source character over the destination character,
+    /// or None if the destination should be left as it is.
+    fn apply(&self, source: TermCharacter, destination: TermCharacter) -> Option<TermCharacter> {
+        // The continuations of wide characters are copied along with them, even though they are spaces
+        let space = source.character == ' ' && source.width != 0;
+        if self.skip_transparent && space && source.bg_color.a == 0.0 {
+            return None;
+        }
+        let source = TermCharacter {
+            fg_color: multiply(source.fg_color, self.tint),
+            bg_color: multiply(source.bg_color, self.tint),
+            ..source
+        };
+        let (fg_alpha, bg_alpha) = match self.alpha {
+            Some(alpha) => alpha,
+            None => return Some(source),
+        };
+
+        let source_bg_alpha = source.bg_color.a * bg_alpha;
+        let bg_color = destination
+            .bg_color
+            .blend(source.bg_color.with_alpha(source_bg_alpha));
+        if space {
+            Some(TermCharacter {
+                bg_color,
+                ..destination
+            })
+        } else {
+            let mut fg_color = source.fg_color;
+            fg_color.a *= fg_alpha;
+            Some(TermCharacter {
+                fg_color,
+                bg_color,
+                ..source
+            })
+        }
+    }
+}
+
+impl TextBuffer {
+    /// Copies the characters in the rectangle at the given position (x, y) with the given dimensions (width, height)
+    /// in the source text buffer to the given destination position in this text buffer. Example:
+    ///
+    /// ```
+    /// use glerminal::terminal::TerminalBuilder;
+    /// use glerminal::text_buffer::{BlitOptions, TextBuffer};
+    ///
+    /// let terminal = TerminalBuilder::new().with_headless(true).build();
+    /// let mut screen = TextBuffer::new(&terminal, (80, 24)).unwrap();
+    /// let mut panel = TextBuffer::new(&terminal, (10, 3)).unwrap();
+    /// panel.write("Inventory");
+    ///
+    /// let options = BlitOptions::new().with_skip_transparent(true);
+    /// screen.blit(&panel, (0, 0), (10, 3), (5, 5), &options);
+    /// assert_eq!(screen.get_character(5, 5).get_char(), 'I');
+    /// ```
+    ///
+    /// The parts of the rectangle that are outside either text buffer are left out. The characters are copied from the
+    /// source's current characters, regardless of its scrollback view. See [`BlitOptions`](struct.BlitOptions.html)
+    /// for skipping transparent characters, blending and tinting.
+    pub fn blit(
+        &mut self,
+        source: &TextBuffer,
+        position: (i32, i32),
+        dimensions: (i32, i32),
+        destination: (i32, i32),
+        options: &BlitOptions,
+    ) {
+        // The columns of the rectangle that are inside both text buffers
+        let left = 0.max(-position.0).max(-destination.0);
+        let right = dimensions
+            .0
+            .min(source.width - position.0)
+            .min(self.width - destination.0);
+        if left >= right {
+            return;
+        }
+        for y in 0..dimensions.1 {
+            let (source_y, destination_y) = (position.1 + y, destination.1 + y);
+            if source.out_of_bounds(0, source_y) || self.out_of_bounds(0, destination_y) {
+                continue;
+            }
+            let row: Vec<TermCharacter> = (left..right)
+                .map(|x| {
+                    let source = source.get_character(position.0 + x, source_y);
+                    let destination = self.get_character(destination.0 + x, destination_y);
+                    options.apply(source, destination).unwrap_or(destination)
+                })
+                .collect();
+            // Wide characters cut in half by the sides of the rectangle are emptied
+            self.put_cells(destination.0 + left, destination_y, &row);
+        }
+    }
+}
+
+fn multiply(color: Color, tint: Color) -> Color {
+    Color::new(
+        color.r * tint.r,
+        color.g * tint.g,
+        color.b * tint.b,
+        color.a * tint.a,
+    )
+}
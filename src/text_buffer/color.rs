@@ -0,0 +1,289 @@
+//! The `Color` type used for the colors of a `TextBuffer`, and the ANSI and xterm-256 palettes.
+
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
+/// Creates a color from red, green, blue and alpha values from 0 to 255, usable in constants.
+macro_rules! rgba_u8 {
+    ($r:expr, $g:expr, $b:expr, $a:expr) => {
+        Color {
+            r: $r as f32 / 255.0,
+            g: $g as f32 / 255.0,
+            b: $b as f32 / 255.0,
+            a: $a as f32 / 255.0,
+        }
+    };
+}
+
+/// Represents a color with values from 0.0 to 1.0 (red, green, blue, alpha). Example:
+///
+/// ```
+/// use glerminal::terminal::TerminalBuilder;
+/// use glerminal::text_buffer::{Color, TextBuffer};
+///
+/// let terminal = TerminalBuilder::new().with_headless(true).build();
+/// let mut text_buffer = TextBuffer::new(&terminal, (80, 24)).unwrap();
+///
+/// text_buffer.change_cursor_fg_color(Color::from_hex("#ffa500").unwrap());
+/// text_buffer.change_cursor_bg_color(Color::xterm(17));
+/// // Arrays work too
+/// text_buffer.change_cursor_fg_color([1.0, 0.0, 0.0, 1.0]);
+/// assert_eq!(text_buffer.get_cursor_fg_color(), [1.0, 0.0, 0.0, 1.0]);
+/// ```
+///
+/// `Color` converts from and into `[f32; 4]`, can be compared with one and indexed like one,
+/// so arrays can be used wherever colors are given to a `TextBuffer`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Color {
+    /// Red, from 0.0 to 1.0
+    pub r: f32,
+    /// Green, from 0.0 to 1.0
+    pub g: f32,
+    /// Blue, from 0.0 to 1.0
+    pub b: f32,
+    /// Alpha (opacity), from 0.0 to 1.0
+    pub a: f32,
+}
+
+impl Color {
+    /// Fully transparent black, the default background color
+    pub const TRANSPARENT: Color = rgba_u8!(0, 0, 0, 0);
+
+    /// ANSI color 0
+    pub const BLACK: Color = rgba_u8!(0, 0, 0, 255);
+    /// ANSI color 1
+    pub const RED: Color = rgba_u8!(205, 0, 0, 255);
+    /// ANSI color 2
+    pub const GREEN: Color = rgba_u8!(0, 205, 0, 255);
+    /// ANSI color 3
+    pub const YELLOW: Color = rgba_u8!(205, 205, 0, 255);
+    /// ANSI color 4
+    pub const BLUE: Color = rgba_u8!(0, 0, 238, 255);
+    /// ANSI color 5
+    pub const MAGENTA: Color = rgba_u8!(205, 0, 205, 255);
+    /// ANSI color 6
+    pub const CYAN: Color = rgba_u8!(0, 205, 205, 255);
+    /// ANSI color 7
+    pub const WHITE: Color = rgba_u8!(229, 229, 229, 255);
+    /// ANSI color 8
+    pub const BRIGHT_BLACK: Color = rgba_u8!(127, 127, 127, 255);
+    /// ANSI color 9
+    pub const BRIGHT_RED: Color = rgba_u8!(255, 0, 0, 255);
+    /// ANSI color 10
+    pub const BRIGHT_GREEN: Color = rgba_u8!(0, 255, 0, 255);
+    /// ANSI color 11
+    pub const BRIGHT_YELLOW: Color = rgba_u8!(255, 255, 0, 255);
+    /// ANSI color 12
+    pub const BRIGHT_BLUE: Color = rgba_u8!(92, 92, 255, 255);
+    /// ANSI color 13
+    pub const BRIGHT_MAGENTA: Color = rgba_u8!(255, 0, 255, 255);
+    /// ANSI color 14
+    pub const BRIGHT_CYAN: Color = rgba_u8!(0, 255, 255, 255);
+    /// ANSI color 15
+    pub const BRIGHT_WHITE: Color = rgba_u8!(255, 255, 255, 255);
+
+    /// The 16 ANSI colors, in the order of their indices (the xterm defaults)
+    pub const ANSI_COLORS: [Color; 16] = [
+        Color::BLACK,
+        Color::RED,
+        Color::GREEN,
+        Color::YELLOW,
+        Color::BLUE,
+        Color::MAGENTA,
+        Color::CYAN,
+        Color::WHITE,
+        Color::BRIGHT_BLACK,
+        Color::BRIGHT_RED,
+        Color::BRIGHT_GREEN,
+        Color::BRIGHT_YELLOW,
+        Color::BRIGHT_BLUE,
+        Color::BRIGHT_MAGENTA,
+        Color::BRIGHT_CYAN,
+        Color::BRIGHT_WHITE,
+    ];
+
+    /// Creates a color from red, green, blue and alpha values from 0.0 to 1.0.
+    pub fn new(r: f32, g: f32, b: f32, a: f32) -> Color {
+        Color { r, g, b, a }
+    }
+
+    /// Creates an opaque color from red, green and blue values from 0 to 255.
+    pub fn from_rgb_u8(r: u8, g: u8, b: u8) -> Color {
+        rgba_u8!(r, g, b, 255)
+    }
+
+    /// Creates a color from red, green, blue and alpha values from 0 to 255.
+    pub fn from_rgba_u8(r: u8, g: u8, b: u8, a: u8) -> Color {
+        rgba_u8!(r, g, b, a)
+    }
+
+    /// Parses a color from a hex string, ie. `"#ff8000"`. The `#` is optional, and the string can have 3 (`rgb`),
+    /// 4 (`rgba`), 6 (`rrggbb`) or 8 (`rrggbbaa`) digits. Colors without alpha are opaque.
+    pub fn from_hex(hex: &str) -> Result<Color, String> {
+        let digits = hex.trim_start_matches('#');
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("{} is not a valid hex color", hex));
+        }
+        let values: Vec<u8> = match digits.len() {
+            3 | 4 => digits
+                .chars()
+                .map(|c| c.to_digit(16).unwrap() as u8 * 17)
+                .collect(),
+            6 | 8 => (0..digits.len() / 2)
+                .map(|i| u8::from_str_radix(&digits[i * 2..i * 2 + 2], 16).unwrap())
+                .collect(),
+            _ => return Err(format!("{} is not a valid hex color", hex)),
+        };
+        let alpha = values.get(3).cloned().unwrap_or(255);
+        Ok(rgba_u8!(values[0], values[1], values[2], alpha))
+    }
+
+    /// Creates an opaque color from hue (in degrees), saturation (0.0 - 1.0) and value (0.0 - 1.0).
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> Color {
+        let chroma = value * saturation;
+        Color::from_hue(hue, chroma, value - chroma)
+    }
+
+    /// Creates an opaque color from hue (in degrees), saturation (0.0 - 1.0) and lightness (0.0 - 1.0).
+    pub fn from_hsl(hue: f32, saturation: f32, lightness: f32) -> Color {
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        Color::from_hue(hue, chroma, lightness - chroma / 2.0)
+    }
+
+    /// Returns the color with the given index in the xterm-256 palette: 0 - 15 are the
+    /// [ANSI colors](#associatedconstant.ANSI_COLORS), 16 - 231 are a 6x6x6 color cube and 232 - 255 are grays.
+    pub fn xterm(index: u8) -> Color {
+        match index {
+            0..=15 => Color::ANSI_COLORS[index as usize],
+            16..=231 => {
+                let index = index - 16;
+                let level = |step: u8| if step == 0 { 0 } else { 55 + step * 40 };
+                Color::from_rgb_u8(level(index / 36), level(index / 6 % 6), level(index % 6))
+            }
+            _ => {
+                let gray = 8 + (index - 232) * 10;
+                Color::from_rgb_u8(gray, gray, gray)
+            }
+        }
+    }
+
+    /// Returns the color with its alpha replaced with the given one.
+    pub fn with_alpha(self, alpha: f32) -> Color {
+        Color { a: alpha, ..self }
+    }
+
+    /// Returns the color between this color and the other one, where `amount` is from 0.0 (this color)
+    /// to 1.0 (the other color). All four components are interpolated.
+    pub fn lerp(self, other: Color, amount: f32) -> Color {
+        let amount = amount.max(0.0).min(1.0);
+        Color {
+            r: self.r + (other.r - self.r) * amount,
+            g: self.g + (other.g - self.g) * amount,
+            b: self.b + (other.b - self.b) * amount,
+            a: self.a + (other.a - self.a) * amount,
+        }
+    }
+
+    /// Returns the color that results from drawing the other color over this one, according to the other color's alpha.
+    pub fn blend(self, other: Color) -> Color {
+        let alpha = other.a + self.a * (1.0 - other.a);
+        if alpha <= 0.0 {
+            return Color::TRANSPARENT;
+        }
+        let mix =
+            |under: f32, over: f32| (over * other.a + under * self.a * (1.0 - other.a)) / alpha;
+        Color {
+            r: mix(self.r, other.r),
+            g: mix(self.g, other.g),
+            b: mix(self.b, other.b),
+            a: alpha,
+        }
+    }
+
+    /// Returns the color as an array of red, green, blue and alpha.
+    pub fn to_array(self) -> [f32; 4] {
+        [self.r, self.g, self.b, self.a]
+    }
+
+    /// Returns the color as red, green, blue and alpha values from 0 to 255.
+    pub fn to_rgba_u8(self) -> [u8; 4] {
+        let to_u8 = |value: f32| (value.max(0.0).min(1.0) * 255.0).round() as u8;
+        [to_u8(self.r), to_u8(self.g), to_u8(self.b), to_u8(self.a)]
+    }
+
+    /// Creates an opaque color from the hue, chroma and the value of the smallest component.
+    fn from_hue(hue: f32, chroma: f32, min: f32) -> Color {
+        let sector = (hue % 360.0 + 360.0) % 360.0 / 60.0;
+        let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+        let (r, g, b) = match sector as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        Color::new(r + min, g + min, b + min, 1.0)
+    }
+}
+
+impl FromStr for Color {
+    type Err = String;
+
+    /// Parses a color from a hex string, see [`from_hex`](#method.from_hex).
+    fn from_str(hex: &str) -> Result<Color, String> {
+        Color::from_hex(hex)
+    }
+}
+
+impl From<[f32; 4]> for Color {
+    fn from(color: [f32; 4]) -> Color {
+        Color::new(color[0], color[1], color[2], color[3])
+    }
+}
+
+impl From<Color> for [f32; 4] {
+    fn from(color: Color) -> [f32; 4] {
+        color.to_array()
+    }
+}
+
+impl Index<usize> for Color {
+    type Output = f32;
+
+    /// Returns the component with the given index, in the order red, green, blue and alpha, like the `[f32; 4]` colors
+    /// of earlier versions. Panics if the index is above 3.
+    fn index(&self, index: usize) -> &f32 {
+        match index {
+            0 => &self.r,
+            1 => &self.g,
+            2 => &self.b,
+            3 => &self.a,
+            _ => panic!("Color index out of bounds: {}", index),
+        }
+    }
+}
+
+impl IndexMut<usize> for Color {
+    fn index_mut(&mut self, index: usize) -> &mut f32 {
+        match index {
+            0 => &mut self.r,
+            1 => &mut self.g,
+            2 => &mut self.b,
+            3 => &mut self.a,
+            _ => panic!("Color index out of bounds: {}", index),
+        }
+    }
+}
+
+impl PartialEq<[f32; 4]> for Color {
+    fn eq(&self, other: &[f32; 4]) -> bool {
+        self.to_array() == *other
+    }
+}
+
+impl PartialEq<Color> for [f32; 4] {
+    fn eq(&self, other: &Color) -> bool {
+        *self == other.to_array()
+    }
+}
@@ -0,0 +1,264 @@
+//! Moving, saving and drawing the cursor of a `TextBuffer`.
+
+use super::{Color, TextBuffer};
+
+/// The shape the cursor of a [`TextBuffer`](struct.TextBuffer.html) is drawn with,
+/// see [`set_cursor_visible`](struct.TextBuffer.html#method.set_cursor_visible).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CursorStyle {
+    /// The whole cell is filled (default).
+    Block,
+    /// A line at the bottom of the cell.
+    Underline,
+    /// A line at the left side of the cell.
+    Bar,
+}
+
+impl Default for CursorStyle {
+    fn default() -> CursorStyle {
+        CursorStyle::Block
+    }
+}
+
+/// Decides what happens when the cursor of a [`TextBuffer`](struct.TextBuffer.html) is moved outside the buffer,
+/// ie. with [`move_cursor`](struct.TextBuffer.html#method.move_cursor) or
+/// [`move_cursor_relative`](struct.TextBuffer.html#method.move_cursor_relative).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CursorBoundsPolicy {
+    /// The cursor stays where it was (default).
+    Ignore,
+    /// The cursor is moved to the closest position inside the buffer.
+    Clamp,
+    /// The `try_` versions of the movement methods return an error, and the others panic. The cursor stays where it was.
+    Error,
+}
+
+impl Default for CursorBoundsPolicy {
+    fn default() -> CursorBoundsPolicy {
+        CursorBoundsPolicy::Ignore
+    }
+}
+
+/// How the cursor of a `TextBuffer` is drawn
+pub(crate) struct CursorDisplay {
+    visible: bool,
+    style: CursorStyle,
+    color: Color,
+    blink_rate: f32,
+}
+
+impl CursorDisplay {
+    pub(crate) fn new() -> CursorDisplay {
+        CursorDisplay {
+            visible: false,
+            style: CursorStyle::default(),
+            color: Color::new(1.0, 1.0, 1.0, 0.5),
+            blink_rate: 1.0,
+        }
+    }
+}
+
+impl TextBuffer {
+    /// Sets what happens when the cursor is moved outside the buffer, see [`CursorBoundsPolicy`](enum.CursorBoundsPolicy.html).
+    pub fn set_cursor_bounds_policy(&mut self, policy: CursorBoundsPolicy) {
+        self.cursor_bounds_policy = policy;
+    }
+
+    /// Returns what happens when the cursor is moved outside the buffer,
+    /// see [`set_cursor_bounds_policy`](#method.set_cursor_bounds_policy).
+    pub fn get_cursor_bounds_policy(&self) -> CursorBoundsPolicy {
+        self.cursor_bounds_policy
+    }
+
+    /// Moves the cursor by the given amount of columns (right for positive) and rows (down for positive). Example:
+    ///
+    /// ```
+    /// use glerminal::terminal::TerminalBuilder;
+    /// use glerminal::text_buffer::{CursorBoundsPolicy, TextBuffer};
+    ///
+    /// let terminal = TerminalBuilder::new().with_headless(true).build();
+    /// let mut text_buffer = TextBuffer::new(&terminal, (80, 24)).unwrap();
+    ///
+    /// text_buffer.move_cursor(10, 10);
+    /// text_buffer.move_cursor_relative(-2, 1);
+    /// assert_eq!(text_buffer.get_cursor_position(), (8, 11));
+    ///
+    /// text_buffer.set_cursor_bounds_policy(CursorBoundsPolicy::Clamp);
+    /// text_buffer.move_cursor_relative(0, -100);
+    /// assert_eq!(text_buffer.get_cursor_position(), (8, 0));
+    /// ```
+    ///
+    /// Unlike writing, the cursor doesn't move to the next row when it's moved past the end of a row. If the new position
+    /// is outside the buffer, what happens depends on the [`CursorBoundsPolicy`](enum.CursorBoundsPolicy.html).
+    ///
+    /// Panics if the new position is outside the buffer and the policy is `CursorBoundsPolicy::Error`,
+    /// see [`try_move_cursor_relative`](#method.try_move_cursor_relative) for a non-panicking version.
+    pub fn move_cursor_relative(&mut self, columns: i32, rows: i32) {
+        let (x, y) = self.get_cursor_position();
+        self.move_cursor(x + columns, y + rows);
+    }
+
+    /// Moves the cursor by the given amount of columns and rows, returning an error if the new position is outside the
+    /// buffer and the [`CursorBoundsPolicy`](enum.CursorBoundsPolicy.html) is `CursorBoundsPolicy::Error`.
+    pub fn try_move_cursor_relative(&mut self, columns: i32, rows: i32) -> Result<(), String> {
+        let (x, y) = self.get_cursor_position();
+        self.try_move_cursor(x + columns, y + rows)
+    }
+
+    /// Moves the cursor to the start of its row.
+    pub fn move_cursor_to_line_start(&mut self) {
+        self.cursor.x = 0;
+        self.cursor.clipped = false;
+    }
+
+    /// Moves the cursor right after the last character of its row that isn't a space, or to the last column if the row
+    /// is full.
+    pub fn move_cursor_to_line_end(&mut self) {
+        let y = self.cursor.y;
+        let end = (0..self.width)
+            .rev()
+            .find(|&x| self.get_character(x, y).get_char() != ' ')
+            .map_or(0, |x| {
+                x + self.get_character(x, y).get_width().max(1) as i32
+            });
+        self.cursor.x = end.min(self.width - 1);
+        self.cursor.clipped = false;
+    }
+
+    /// Saves the state of the cursor (position, colors, shakiness and text attributes) onto a stack,
+    /// to be restored later with [`pop_cursor`](#method.pop_cursor). Example:
+    ///
+    /// ```
+    /// use glerminal::terminal::TerminalBuilder;
+    /// use glerminal::text_buffer::TextBuffer;
+    ///
+    /// let terminal = TerminalBuilder::new().with_headless(true).build();
+    /// let mut text_buffer = TextBuffer::new(&terminal, (80, 24)).unwrap();
+    ///
+    /// text_buffer.write("> ");
+    /// text_buffer.push_cursor();
+    /// text_buffer.move_cursor(70, 0);
+    /// text_buffer.change_cursor_fg_color([1.0, 0.0, 0.0, 1.0]);
+    /// text_buffer.write("HP 3");
+    /// text_buffer.pop_cursor();
+    ///
+    /// assert_eq!(text_buffer.get_cursor_position(), (2, 0));
+    /// assert_eq!(text_buffer.get_cursor_fg_color(), [1.0; 4]);
+    /// ```
+    pub fn push_cursor(&mut self) {
+        self.cursor_stack.push(self.cursor);
+    }
+
+    /// Restores the cursor state that was saved last with [`push_cursor`](#method.push_cursor), removing it from the
+    /// stack. Returns false and leaves the cursor as it is if there are no saved states.
+    ///
+    /// If the buffer has been resized since, the position is moved inside the new dimensions.
+    pub fn pop_cursor(&mut self) -> bool {
+        match self.cursor_stack.pop() {
+            Some(mut cursor) => {
+                if cursor.x >= self.width || cursor.y >= self.height {
+                    cursor.x = cursor.x.min(self.width - 1);
+                    cursor.y = cursor.y.min(self.height - 1);
+                    cursor.clipped = false;
+                }
+                self.cursor = cursor;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns how many cursor states are saved, see [`push_cursor`](#method.push_cursor).
+    pub fn get_cursor_stack_len(&self) -> usize {
+        self.cursor_stack.len()
+    }
+
+    /// Sets whether the cursor is drawn (default false). Example:
+    ///
+    /// ```
+    /// use glerminal::terminal::TerminalBuilder;
+    /// use glerminal::text_buffer::{CursorStyle, TextBuffer};
+    ///
+    /// let terminal = TerminalBuilder::new().with_headless(true).build();
+    /// let mut text_buffer = TextBuffer::new(&terminal, (80, 24)).unwrap();
+    ///
+    /// text_buffer.write("Name: ");
+    /// text_buffer.set_cursor_visible(true);
+    /// text_buffer.set_cursor_style(CursorStyle::Bar);
+    /// text_buffer.set_cursor_blink_rate(2.0);
+    /// terminal.flush(&mut text_buffer);
+    /// ```
+    ///
+    /// The cursor is drawn over the backgrounds of the characters and under the characters themselves, in the
+    /// [style](#method.set_cursor_style) and [color](#method.set_cursor_color) set for it. Like the characters, the cursor
+    /// is updated when the text buffer is flushed. It isn't drawn while the view is scrolled so that the cursor's row
+    /// is not in view, see [`scroll_view_up`](#method.scroll_view_up).
+    pub fn set_cursor_visible(&mut self, visible: bool) {
+        self.cursor_display.visible = visible;
+    }
+
+    /// Returns whether the cursor is drawn, see [`set_cursor_visible`](#method.set_cursor_visible).
+    pub fn is_cursor_visible(&self) -> bool {
+        self.cursor_display.visible
+    }
+
+    /// Sets the shape the cursor is drawn with, see [`CursorStyle`](enum.CursorStyle.html).
+    pub fn set_cursor_style(&mut self, style: CursorStyle) {
+        self.cursor_display.style = style;
+    }
+
+    /// Gets the shape the cursor is drawn with
+    pub fn get_cursor_style(&self) -> CursorStyle {
+        self.cursor_display.style
+    }
+
+    /// Sets the color the cursor is drawn with (default half-transparent white, so that the character under a block
+    /// cursor stays readable).
+    pub fn set_cursor_color<T: Into<Color>>(&mut self, color: T) {
+        self.cursor_display.color = color.into();
+    }
+
+    /// Gets the color the cursor is drawn with
+    pub fn get_cursor_color(&self) -> Color {
+        self.cursor_display.color
+    }
+
+    /// Sets how many times per second the cursor blinks (default 1.0); 0.0 draws the cursor without blinking.
+    /// Negative rates are treated as 0.0.
+    pub fn set_cursor_blink_rate(&mut self, blink_rate: f32) {
+        self.cursor_display.blink_rate = blink_rate.max(0.0);
+    }
+
+    /// Gets how many times per second the cursor blinks
+    pub fn get_cursor_blink_rate(&self) -> f32 {
+        self.cursor_display.blink_rate
+    }
+
+    /// Returns the rectangle (x, y, width, height) the cursor is drawn in, relative to the size of the whole buffer,
+    /// or `None` if the cursor isn't drawn.
+    pub(crate) fn get_cursor_rect(&self) -> Option<(f32, f32, f32, f32)> {
+        let y = self.cursor.y + self.get_scroll_offset() as i32;
+        if !self.cursor_display.visible || y >= self.height {
+            return None;
+        }
+        let x = self.cursor.x;
+        let cell_width = 1.0 / self.width as f32;
+        let cell_height = 1.0 / self.height as f32;
+        // The cursor covers both cells of a wide character
+        let cells = self.get_visible_character(x, y).get_width().max(1) as f32;
+        let (x_off, y_off) = (x as f32 * cell_width, y as f32 * cell_height);
+        Some(match self.cursor_display.style {
+            CursorStyle::Block => (x_off, y_off, cell_width * cells, cell_height),
+            CursorStyle::Underline => {
+                let thickness = cell_height / 6.0;
+                (
+                    x_off,
+                    y_off + cell_height - thickness,
+                    cell_width * cells,
+                    thickness,
+                )
+            }
+            CursorStyle::Bar => (x_off, y_off, cell_width / 6.0, cell_height),
+        })
+    }
+}
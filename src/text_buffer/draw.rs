@@ -0,0 +1,428 @@
+//! Drawing lines, rectangles, boxes and circles into a `TextBuffer`.
+
+use std::collections::VecDeque;
+
+use super::{TermCharacter, TextBuffer};
+
+/// The set of box-drawing characters used by [`TextBuffer::draw_box`](struct.TextBuffer.html#method.draw_box)
+/// and the box-drawing lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoxStyle {
+    /// Single lines: `┌─┐`
+    Single,
+    /// Double lines: `╔═╗`
+    Double,
+    /// Single lines with rounded corners: `╭─╮`
+    Rounded,
+}
+
+impl BoxStyle {
+    fn weight(&self) -> u8 {
+        match *self {
+            BoxStyle::Single | BoxStyle::Rounded => 1,
+            BoxStyle::Double => 2,
+        }
+    }
+}
+
+const UP: usize = 0;
+const RIGHT: usize = 1;
+const DOWN: usize = 2;
+const LEFT: usize = 3;
+
+/// The box-drawing characters and their connections (up, right, down, left), where 1 is a single and 2 a double line.
+const BOX_CHARACTERS: [(char, [u8; 4]); 48] = [
+    ('─', [0, 1, 0, 1]),
+    ('│', [1, 0, 1, 0]),
+    ('┌', [0, 1, 1, 0]),
+    ('┐', [0, 0, 1, 1]),
+    ('└', [1, 1, 0, 0]),
+    ('┘', [1, 0, 0, 1]),
+    ('├', [1, 1, 1, 0]),
+    ('┤', [1, 0, 1, 1]),
+    ('┬', [0, 1, 1, 1]),
+    ('┴', [1, 1, 0, 1]),
+    ('┼', [1, 1, 1, 1]),
+    ('═', [0, 2, 0, 2]),
+    ('║', [2, 0, 2, 0]),
+    ('╔', [0, 2, 2, 0]),
+    ('╗', [0, 0, 2, 2]),
+    ('╚', [2, 2, 0, 0]),
+    ('╝', [2, 0, 0, 2]),
+    ('╠', [2, 2, 2, 0]),
+    ('╣', [2, 0, 2, 2]),
+    ('╦', [0, 2, 2, 2]),
+    ('╩', [2, 2, 0, 2]),
+    ('╬', [2, 2, 2, 2]),
+    ('╒', [0, 2, 1, 0]),
+    ('╓', [0, 1, 2, 0]),
+    ('╕', [0, 0, 1, 2]),
+    ('╖', [0, 0, 2, 1]),
+    ('╘', [1, 2, 0, 0]),
+    ('╙', [2, 1, 0, 0]),
+    ('╛', [1, 0, 0, 2]),
+    ('╜', [2, 0, 0, 1]),
+    ('╞', [1, 2, 1, 0]),
+    ('╟', [2, 1, 2, 0]),
+    ('╡', [1, 0, 1, 2]),
+    ('╢', [2, 0, 2, 1]),
+    ('╤', [0, 2, 1, 2]),
+    ('╥', [0, 1, 2, 1]),
+    ('╧', [1, 2, 0, 2]),
+    ('╨', [2, 1, 0, 1]),
+    ('╪', [1, 2, 1, 2]),
+    ('╫', [2, 1, 2, 1]),
+    ('╴', [0, 0, 0, 1]),
+    ('╵', [1, 0, 0, 0]),
+    ('╶', [0, 1, 0, 0]),
+    ('╷', [0, 0, 1, 0]),
+    // Rounded corners are only read; they're written by `box_character` when asked for
+    ('╭', [0, 1, 1, 0]),
+    ('╮', [0, 0, 1, 1]),
+    ('╰', [1, 1, 0, 0]),
+    ('╯', [1, 0, 0, 1]),
+];
+
+fn box_connections(character: char) -> [u8; 4] {
+    BOX_CHARACTERS
+        .iter()
+        .find(|&&(box_character, _)| box_character == character)
+        .map(|&(_, connections)| connections)
+        .unwrap_or([0; 4])
+}
+
+fn box_character(connections: [u8; 4], rounded: bool) -> Option<char> {
+    if rounded {
+        match connections {
+            [0, 1, 1, 0] => return Some('╭'),
+            [0, 0, 1, 1] => return Some('╮'),
+            [1, 1, 0, 0] => return Some('╰'),
+            [1, 0, 0, 1] => return Some('╯'),
+            _ => {}
+        }
+    }
+    BOX_CHARACTERS
+        .iter()
+        .find(|&&(_, box_connections)| box_connections == connections)
+        .map(|&(character, _)| character)
+}
+
+/// Merges the new connections into the old ones. Lines on the same axis are made the same weight, since there are
+/// no characters for ie. a single line joining a double line, and single directions are extended into full lines.
+fn merge_connections(old: [u8; 4], new: [u8; 4]) -> [u8; 4] {
+    let mut connections = old;
+    for direction in 0..4 {
+        if new[direction] > 0 {
+            connections[direction] = new[direction];
+        }
+    }
+    for &(first, second) in &[(UP, DOWN), (LEFT, RIGHT)] {
+        if connections[first] > 0
+            && connections[second] > 0
+            && connections[first] != connections[second]
+        {
+            let weight = new[first].max(new[second]);
+            connections[first] = weight;
+            connections[second] = weight;
+        }
+    }
+    let count = connections.iter().filter(|&&weight| weight > 0).count();
+    if count == 1 {
+        for direction in 0..4 {
+            if connections[direction] > 0 {
+                connections[(direction + 2) % 4] = connections[direction];
+                break;
+            }
+        }
+    }
+    connections
+}
+
+impl TextBuffer {
+    /// Draws a horizontal line of box-drawing characters to the right from the given position, joining it with
+    /// the box-drawing characters it crosses or touches. Uses the cursor's colors and shakiness.
+    pub fn draw_horizontal_line(&mut self, position: (i32, i32), length: i32, style: BoxStyle) {
+        let (x, y) = position;
+        let (first, last) = self.clip_line(x, length, self.width);
+        for i in first..last {
+            let mut connections = [0; 4];
+            if i > 0 {
+                connections[LEFT] = style.weight();
+            }
+            if i < i128::from(length) - 1 {
+                connections[RIGHT] = style.weight();
+            }
+            self.draw_box_character((i128::from(x) + i) as i32, y, connections, style);
+        }
+    }
+
+    /// Draws a vertical line of box-drawing characters down from the given position, joining it with
+    /// the box-drawing characters it crosses or touches. Uses the cursor's colors and shakiness.
+    pub fn draw_vertical_line(&mut self, position: (i32, i32), length: i32, style: BoxStyle) {
+        let (x, y) = position;
+        let (first, last) = self.clip_line(y, length, self.height);
+        for i in first..last {
+            let mut connections = [0; 4];
+            if i > 0 {
+                connections[UP] = style.weight();
+            }
+            if i < i128::from(length) - 1 {
+                connections[DOWN] = style.weight();
+            }
+            self.draw_box_character(x, (i128::from(y) + i) as i32, connections, style);
+        }
+    }
+
+    /// Draws a line of the given character between the given positions (both inclusive) using Bresenham's line algorithm.
+    /// Uses the cursor's colors and shakiness.
+    pub fn draw_line(&mut self, start: (i32, i32), end: (i32, i32), character: char) {
+        let (start_x, start_y) = (i128::from(start.0), i128::from(start.1));
+        let (delta_x, delta_y) = (i128::from(end.0) - start_x, i128::from(end.1) - start_y);
+        // The line is walked one cell at a time along its longer axis
+        let x_major = delta_x.abs() >= delta_y.abs();
+        let (major, minor, delta_major, delta_minor, limit) = if x_major {
+            (start_x, start_y, delta_x, delta_y, i128::from(self.width))
+        } else {
+            (start_y, start_x, delta_y, delta_x, i128::from(self.height))
+        };
+        let (length, rise) = (delta_major.abs(), delta_minor.abs());
+        // Only the steps that are inside the text buffer along the longer axis are visited
+        let (first, last) = if delta_major < 0 {
+            ((major - limit + 1).max(0), length.min(major))
+        } else {
+            ((-major).max(0), length.min(limit - 1 - major))
+        };
+        for step in first..last + 1 {
+            // The same rounding as the error term of Bresenham's algorithm
+            let offset = if length == 0 {
+                0
+            } else {
+                (2 * step * rise + length) / (2 * length)
+            };
+            let major = major + step * delta_major.signum();
+            let minor = minor + offset * delta_minor.signum();
+            let (x, y) = if x_major {
+                (major, minor)
+            } else {
+                (minor, major)
+            };
+            self.draw_character(x as i32, y as i32, character);
+        }
+    }
+
+    /// Fills the rectangle at the given position (x, y) with the given dimensions (width, height) with the given character.
+    /// Uses the cursor's colors and shakiness.
+    pub fn fill_rect(&mut self, position: (i32, i32), dimensions: (i32, i32), character: char) {
+        let (left, top) = (position.0.max(0), position.1.max(0));
+        let right = position.0.saturating_add(dimensions.0).min(self.width);
+        let bottom = position.1.saturating_add(dimensions.1).min(self.height);
+        for y in top..bottom {
+            for x in left..right {
+                self.draw_character(x, y, character);
+            }
+        }
+    }
+
+    /// Draws the outline of the rectangle at the given position (x, y) with the given dimensions (width, height)
+    /// with the given character. Uses the cursor's colors and shakiness.
+    pub fn draw_rect(&mut self, position: (i32, i32), dimensions: (i32, i32), character: char) {
+        let (width, height) = dimensions;
+        if width <= 0 || height <= 0 {
+            return;
+        }
+        let (left, top) = position;
+        let (right, bottom) = (
+            left.saturating_add(width - 1),
+            top.saturating_add(height - 1),
+        );
+        for x in left.max(0)..right.min(self.width - 1) + 1 {
+            self.draw_character(x, top, character);
+            self.draw_character(x, bottom, character);
+        }
+        for y in top.max(0)..bottom.min(self.height - 1) + 1 {
+            self.draw_character(left, y, character);
+            self.draw_character(right, y, character);
+        }
+    }
+
+    /// Draws a box of box-drawing characters around the rectangle at the given position (x, y) with the given
+    /// dimensions (width, height), including the outermost cells. Example:
+    ///
+    /// ```
+    /// use glerminal::terminal::TerminalBuilder;
+    /// use glerminal::text_buffer::{BoxStyle, TextBuffer};
+    ///
+    /// let terminal = TerminalBuilder::new().with_headless(true).build();
+    /// let mut text_buffer = TextBuffer::new(&terminal, (80, 24)).unwrap();
+    ///
+    /// text_buffer.draw_box((0, 0), (20, 10), BoxStyle::Double);
+    /// text_buffer.draw_horizontal_line((0, 2), 20, BoxStyle::Single);
+    /// assert_eq!(text_buffer.get_character(0, 0).get_char(), '╔');
+    /// assert_eq!(text_buffer.get_character(0, 2).get_char(), '╟');
+    /// ```
+    ///
+    /// The box is joined with the box-drawing characters under it, so boxes and lines that cross or touch each other
+    /// get the correct junctions. Uses the cursor's colors and shakiness.
+    pub fn draw_box(&mut self, position: (i32, i32), dimensions: (i32, i32), style: BoxStyle) {
+        let (width, height) = dimensions;
+        if width <= 0 || height <= 0 {
+            return;
+        }
+        let (left, top) = position;
+        let (right, bottom) = (
+            left.saturating_add(width - 1),
+            top.saturating_add(height - 1),
+        );
+        let weight = style.weight();
+        for y in top.max(0)..bottom.min(self.height - 1) + 1 {
+            for x in left.max(0)..right.min(self.width - 1) + 1 {
+                let horizontal_edge = y == top || y == bottom;
+                let vertical_edge = x == left || x == right;
+                if !horizontal_edge && !vertical_edge {
+                    continue;
+                }
+                let mut connections = [0; 4];
+                if horizontal_edge && x > left {
+                    connections[LEFT] = weight;
+                }
+                if horizontal_edge && x < right {
+                    connections[RIGHT] = weight;
+                }
+                if vertical_edge && y > top {
+                    connections[UP] = weight;
+                }
+                if vertical_edge && y < bottom {
+                    connections[DOWN] = weight;
+                }
+                self.draw_box_character(x, y, connections, style);
+            }
+        }
+    }
+
+    /// Draws the outline of a circle of the given character with the given center and radius,
+    /// using the midpoint circle algorithm. Uses the cursor's colors and shakiness.
+    pub fn draw_circle(&mut self, center: (i32, i32), radius: i32, character: char) {
+        if radius < 0 {
+            return;
+        }
+        let (center_x, center_y) = (i128::from(center.0), i128::from(center.1));
+        let radius = i128::from(radius);
+        // In the octant where x >= y, the midpoint circle algorithm picks the smallest x for which x² + x + y² >= r²,
+        // so each cell can be checked on its own
+        let on_outline = |major: i128, minor: i128| {
+            let outside = |x: i128| x * x + x + minor * minor >= radius * radius;
+            outside(major) && (major == 0 || !outside(major - 1))
+        };
+        let (left, top, right, bottom) = self.clip_circle(center_x, center_y, radius);
+        for y in top..bottom + 1 {
+            for x in left..right + 1 {
+                let (offset_x, offset_y) = ((x - center_x).abs(), (y - center_y).abs());
+                if on_outline(offset_x.max(offset_y), offset_x.min(offset_y)) {
+                    self.draw_character(x as i32, y as i32, character);
+                }
+            }
+        }
+    }
+
+    /// Fills a circle with the given center and radius with the given character.
+    /// Uses the cursor's colors and shakiness.
+    pub fn fill_circle(&mut self, center: (i32, i32), radius: i32, character: char) {
+        if radius < 0 {
+            return;
+        }
+        let (center_x, center_y) = (i128::from(center.0), i128::from(center.1));
+        let radius = i128::from(radius);
+        let (left, top, right, bottom) = self.clip_circle(center_x, center_y, radius);
+        for y in top..bottom + 1 {
+            for x in left..right + 1 {
+                let (offset_x, offset_y) = (x - center_x, y - center_y);
+                if offset_x * offset_x + offset_y * offset_y <= radius * radius + radius {
+                    self.draw_character(x as i32, y as i32, character);
+                }
+            }
+        }
+    }
+
+    /// Replaces the character at the given position, and all the characters connected to it (horizontally or vertically)
+    /// that have the same character and background color, with the given character.
+    /// Uses the cursor's colors and shakiness.
+    pub fn flood_fill(&mut self, position: (i32, i32), character: char) {
+        let (x, y) = position;
+        if self.out_of_bounds(x, y) {
+            return;
+        }
+        let target = self.get_character(x, y);
+        let matches = |other: TermCharacter| {
+            other.character == target.character && other.bg_color == target.bg_color
+        };
+
+        let mut visited = vec![false; self.chars.len()];
+        let mut queue = VecDeque::new();
+        queue.push_back(position);
+        while let Some((x, y)) = queue.pop_front() {
+            if self.out_of_bounds(x, y) {
+                continue;
+            }
+            let index = (y * self.width + x) as usize;
+            if visited[index] || !matches(self.chars[index]) {
+                continue;
+            }
+            visited[index] = true;
+            self.draw_character(x, y, character);
+            queue.push_back((x + 1, y));
+            queue.push_back((x - 1, y));
+            queue.push_back((x, y + 1));
+            queue.push_back((x, y - 1));
+        }
+    }
+
+    /// Returns the steps (first inclusive, last exclusive) of a line of the given length, starting from the given
+    /// coordinate, that are inside the text buffer's given dimension.
+    fn clip_line(&self, start: i32, length: i32, limit: i32) -> (i128, i128) {
+        let start = i128::from(start);
+        (
+            (-start).max(0),
+            i128::from(length).min(i128::from(limit) - start),
+        )
+    }
+
+    /// Returns the part (left, top, right, bottom, all inclusive) of the bounding box of the circle that is inside
+    /// the text buffer.
+    fn clip_circle(
+        &self,
+        center_x: i128,
+        center_y: i128,
+        radius: i128,
+    ) -> (i128, i128, i128, i128) {
+        (
+            (center_x - radius).max(0),
+            (center_y - radius).max(0),
+            (center_x + radius).min(i128::from(self.width) - 1),
+            (center_y + radius).min(i128::from(self.height) - 1),
+        )
+    }
+
+    /// Puts the character to the given position with the cursor's style, if the position is inside the text buffer.
+    fn draw_character(&mut self, x: i32, y: i32, character: char) {
+        if !self.out_of_bounds(x, y) {
+            let character = self.cursor.character(character);
+            self.set_cell(x, y, character);
+        }
+    }
+
+    fn draw_box_character(&mut self, x: i32, y: i32, connections: [u8; 4], style: BoxStyle) {
+        if self.out_of_bounds(x, y) {
+            return;
+        }
+        let old = box_connections(self.get_character(x, y).character);
+        let mut connections = connections;
+        if connections == [0; 4] {
+            // A single cell line or box is drawn as a horizontal line
+            connections = [0, style.weight(), 0, style.weight()];
+        }
+        let connections = merge_connections(old, connections);
+        if let Some(character) = box_character(connections, style == BoxStyle::Rounded) {
+            self.draw_character(x, y, character);
+        }
+    }
+}
@@ -0,0 +1,342 @@
+//! Writing text into a rectangle of a `TextBuffer`, with word wrapping and alignment.
+
+use std::mem;
+
+use super::parser::Parser;
+use super::{width, TermCharacter, TextBuffer};
+
+/// The horizontal alignment of the lines in a [`TextLayout`](struct.TextLayout.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HorizontalAlignment {
+    /// Lines start at the left edge of the rectangle (default).
+    Left,
+    /// Lines are centered in the rectangle.
+    Center,
+    /// Lines end at the right edge of the rectangle.
+    Right,
+    /// Spaces between words are widened so that lines fill the whole width of the rectangle,
+    /// except for the last line of each paragraph, which is aligned left.
+    Justify,
+}
+
+/// The vertical alignment of the text in a [`TextLayout`](struct.TextLayout.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerticalAlignment {
+    /// The text starts at the top of the rectangle (default).
+    Top,
+    /// The text is centered in the rectangle.
+    Middle,
+    /// The text ends at the bottom of the rectangle.
+    Bottom,
+}
+
+/// What happens to the text that does not fit into the rectangle of a [`TextLayout`](struct.TextLayout.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextOverflow {
+    /// The lines that don't fit are left out (default).
+    Clip,
+    /// The lines that don't fit are left out, and the last line that fits ends with `...`.
+    Ellipsis,
+}
+
+/// Lays out text into a rectangle of a [`TextBuffer`](struct.TextBuffer.html), wrapping lines between words. Example:
+///
+/// ```
+/// use glerminal::terminal::TerminalBuilder;
+/// use glerminal::text_buffer::TextBuffer;
+/// use glerminal::text_buffer::layout::{HorizontalAlignment, TextLayout, TextOverflow};
+///
+/// let terminal = TerminalBuilder::new().with_headless(true).build();
+/// let mut text_buffer = TextBuffer::new(&terminal, (80, 24)).unwrap();
+///
+/// let lines = TextLayout::new((2, 2), (20, 5))
+///     .with_horizontal_alignment(HorizontalAlignment::Center)
+///     .with_overflow(TextOverflow::Ellipsis)
+///     .write(&mut text_buffer, "The quick brown fox jumps over the lazy dog.");
+/// assert_eq!(lines, 3);
+/// ```
+///
+/// Words longer than the width of the rectangle are broken with a hyphen, unless hyphenation is disabled.
+/// Newlines in the text start new paragraphs. The text is written with the cursor's style, but the cursor is not moved,
+/// and the cells of the rectangle that the text does not cover are left as they are.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextLayout {
+    position: (i32, i32),
+    dimensions: (i32, i32),
+    horizontal_alignment: HorizontalAlignment,
+    vertical_alignment: VerticalAlignment,
+    overflow: TextOverflow,
+    hyphenation: bool,
+}
+
+/// A line of laid out text: the words, and the spaces that follow them.
+struct Line {
+    words: Vec<(Vec<TermCharacter>, Option<TermCharacter>)>,
+    ends_paragraph: bool,
+}
+
+impl Line {
+    fn new() -> Line {
+        Line {
+            words: Vec::new(),
+            ends_paragraph: false,
+        }
+    }
+
+    fn len(&self) -> usize {
+        let characters: usize = self.words.iter().map(|&(ref word, _)| word.len()).sum();
+        characters + self.words.len().saturating_sub(1)
+    }
+}
+
+impl TextLayout {
+    /// Creates a new layout for the rectangle at the given position (x, y) with the given dimensions (width, height).
+    pub fn new(position: (i32, i32), dimensions: (i32, i32)) -> TextLayout {
+        TextLayout {
+            position,
+            dimensions,
+            horizontal_alignment: HorizontalAlignment::Left,
+            vertical_alignment: VerticalAlignment::Top,
+            overflow: TextOverflow::Clip,
+            hyphenation: true,
+        }
+    }
+
+    /// Sets the horizontal alignment of the lines.
+    pub fn with_horizontal_alignment(mut self, alignment: HorizontalAlignment) -> TextLayout {
+        self.horizontal_alignment = alignment;
+        self
+    }
+
+    /// Sets the vertical alignment of the text.
+    pub fn with_vertical_alignment(mut self, alignment: VerticalAlignment) -> TextLayout {
+        self.vertical_alignment = alignment;
+        self
+    }
+
+    /// Sets what happens to the text that does not fit into the rectangle.
+    pub fn with_overflow(mut self, overflow: TextOverflow) -> TextLayout {
+        self.overflow = overflow;
+        self
+    }
+
+    /// Sets whether words longer than the width of the rectangle are broken with a hyphen (default true).
+    /// If false, they are broken without one.
+    pub fn with_hyphenation(mut self, hyphenation: bool) -> TextLayout {
+        self.hyphenation = hyphenation;
+        self
+    }
+
+    /// Writes the text into the rectangle with the cursor's style, and returns the number of lines written.
+    pub fn write<T: Into<String>>(&self, text_buffer: &mut TextBuffer, text: T) -> usize {
+        let style = text_buffer.cursor.character(' ');
+        let characters = text
+            .into()
+            .chars()
+            .map(|character| TermCharacter { character, ..style })
+            .collect();
+        self.write_characters(text_buffer, characters)
+    }
+
+    /// Writes text with [`Parser`](../parser/struct.Parser.html) markup into the rectangle, and returns the number of lines written.
+    pub fn write_parsed<T: Into<String>>(
+        &self,
+        text_buffer: &mut TextBuffer,
+        parser: &Parser,
+        text: T,
+    ) -> usize {
+        let default_style = (
+            text_buffer.get_cursor_fg_color(),
+            text_buffer.get_cursor_bg_color(),
+            text_buffer.get_cursor_shakiness(),
+        );
+        let attributes = text_buffer.get_cursor_attributes();
+        let (characters, _) = parser.parse(&text.into(), default_style);
+        let characters = characters
+            .into_iter()
+            .map(|character| TermCharacter {
+                attributes,
+                ..character
+            })
+            .collect();
+        self.write_characters(text_buffer, characters)
+    }
+
+    fn write_characters(
+        &self,
+        text_buffer: &mut TextBuffer,
+        characters: Vec<TermCharacter>,
+    ) -> usize {
+        let (width, height) = self.dimensions;
+        if width <= 0 || height <= 0 {
+            return 0;
+        }
+        let width = width as usize;
+        let height = height as usize;
+
+        let characters = width::to_cells(characters, width >= 2);
+        let mut lines = self.wrap(characters, width);
+        if lines.len() > height {
+            lines.truncate(height);
+            if self.overflow == TextOverflow::Ellipsis {
+                add_ellipsis(&mut lines[height - 1], width);
+            }
+        }
+
+        let offset_y = match self.vertical_alignment {
+            VerticalAlignment::Top => 0,
+            VerticalAlignment::Middle => (height - lines.len()) / 2,
+            VerticalAlignment::Bottom => height - lines.len(),
+        };
+        for (index, line) in lines.iter().enumerate() {
+            let justify =
+                self.horizontal_alignment == HorizontalAlignment::Justify && !line.ends_paragraph;
+            let characters = line_characters(line, if justify { width } else { 0 });
+            let offset_x = match self.horizontal_alignment {
+                HorizontalAlignment::Left | HorizontalAlignment::Justify => 0,
+                HorizontalAlignment::Center => (width - characters.len()) / 2,
+                HorizontalAlignment::Right => width - characters.len(),
+            };
+
+            let x = self.position.0 + offset_x as i32;
+            let y = self.position.1 + (offset_y + index) as i32;
+            text_buffer.put_cells(x, y, &characters);
+        }
+
+        lines.len()
+    }
+
+    /// Wraps the characters into lines of at most the given width.
+    fn wrap(&self, characters: Vec<TermCharacter>, width: usize) -> Vec<Line> {
+        let mut lines = Vec::new();
+        let mut line = Line::new();
+        let mut word: Vec<TermCharacter> = Vec::new();
+
+        for character in characters {
+            match character.character {
+                // The continuations of wide characters are spaces too, but they belong to their words
+                ' ' | '\t' | '\n' if character.width != 0 => {
+                    let space = TermCharacter {
+                        character: ' ',
+                        ..character
+                    };
+                    if !word.is_empty() {
+                        let finished_word = mem::replace(&mut word, Vec::new());
+                        self.add_word(&mut lines, &mut line, finished_word, space, width);
+                    }
+                    if character.character == '\n' {
+                        line.ends_paragraph = true;
+                        lines.push(line);
+                        line = Line::new();
+                    }
+                }
+                '\r' => {}
+                _ => word.push(character),
+            }
+        }
+        if !word.is_empty() {
+            let space = word[word.len() - 1].emptied();
+            self.add_word(&mut lines, &mut line, word, space, width);
+        }
+        if !line.words.is_empty() {
+            line.ends_paragraph = true;
+            lines.push(line);
+        }
+        lines
+    }
+
+    fn add_word(
+        &self,
+        lines: &mut Vec<Line>,
+        line: &mut Line,
+        mut word: Vec<TermCharacter>,
+        space: TermCharacter,
+        width: usize,
+    ) {
+        // Break words that don't fit on a line of their own
+        while word.len() > width {
+            if !line.words.is_empty() {
+                lines.push(mem::replace(line, Line::new()));
+            }
+            // Wide characters are not cut in half, and no hyphen is added next to them
+            let hyphenate = self.hyphenation
+                && width >= 2
+                && word[width - 2].width == 1
+                && word[width - 1].width == 1;
+            let split_at = if hyphenate || word[width].width == 0 {
+                width - 1
+            } else {
+                width
+            };
+            let rest = word.split_off(split_at);
+            if hyphenate {
+                let hyphen = TermCharacter {
+                    character: '-',
+                    ..word[word.len() - 1].emptied()
+                };
+                word.push(hyphen);
+            }
+            line.words.push((word, None));
+            lines.push(mem::replace(line, Line::new()));
+            word = rest;
+        }
+
+        let length = if line.words.is_empty() {
+            word.len()
+        } else {
+            line.len() + 1 + word.len()
+        };
+        if length > width {
+            lines.push(mem::replace(line, Line::new()));
+        }
+        line.words.push((word, Some(space)));
+    }
+}
+
+/// Returns the characters of the line, with the spaces between words widened so that the line is the given width.
+fn line_characters(line: &Line, justify_width: usize) -> Vec<TermCharacter> {
+    let gaps = line.words.len().saturating_sub(1);
+    let extra_spaces = if gaps > 0 {
+        justify_width.saturating_sub(line.len())
+    } else {
+        0
+    };
+
+    let mut characters = Vec::new();
+    for (index, &(ref word, space)) in line.words.iter().enumerate() {
+        characters.extend(word.iter().cloned());
+        if index < gaps {
+            let space = space.unwrap_or(word[word.len() - 1]);
+            let spaces = 1 + extra_spaces / gaps + if index < extra_spaces % gaps { 1 } else { 0 };
+            for _ in 0..spaces {
+                characters.push(space.emptied());
+            }
+        }
+    }
+    characters
+}
+
+/// Ends the line with `...`, removing characters from the end of the line to make room for it if necessary.
+fn add_ellipsis(line: &mut Line, width: usize) {
+    let mut characters = line_characters(line, 0);
+    let style = match characters.last() {
+        Some(character) => *character,
+        None => return,
+    };
+    let dots = width.min(3);
+    characters.truncate(width - dots);
+    if characters.last().map(|c| c.width) == Some(2) {
+        characters.pop();
+    }
+    while characters.last().map(|c| (c.character, c.width)) == Some((' ', 1)) {
+        characters.pop();
+    }
+    for _ in 0..dots {
+        characters.push(TermCharacter {
+            character: '.',
+            ..style.emptied()
+        });
+    }
+    line.words = vec![(characters, None)];
+    line.ends_paragraph = true;
+}
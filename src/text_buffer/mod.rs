@@ -5,6 +5,10 @@
 //! It's often the most efficient way to write things, especially if you have a very structured way of displaying things, but for a more simple-to-use
 //! way of writing, that isn't as structured ie. for a dialogue, you might want to use the Parser.
 //!
+//! Writing handles the control characters `'\n'`, `'\r'`, `'\t'` and backspace, and when the cursor runs past the last row,
+//! the `TextBuffer` either wraps back to the top, scrolls or clips, see [`OverflowMode`](enum.OverflowMode.html).
+//! Rows that scroll off the top can be kept in a scrollback history, see [`set_scrollback_limit`](struct.TextBuffer.html#method.set_scrollback_limit).
+//!
 //! `Parser` is a struct that is able to take in a piece of text and then parse it and change the cursor styles easily using the `TextBuffer`.
 //! The `Parser` can handle tags imilar to BBCode tags, and can change fg, bg and shake, meaning the following tags are available to use mid-text:
 //! - `[fg=color]`
@@ -14,6 +18,27 @@
 //!
 //! The colors the `Parser` uses mid text must be pre-defined however with `add_color`.
 //!
+//! Colors are given as [`Color`](struct.Color.html)s, which can be created from hex strings, HSV/HSL or the ANSI and
+//! xterm-256 palettes; plain `[f32; 4]` arrays (red, green, blue, alpha) work too.
+//!
+//! For text that should wrap between words and be aligned inside a rectangle of the `TextBuffer` (ie. dialogue boxes),
+//! use [`TextLayout`](layout/struct.TextLayout.html), which also understands the `Parser`'s tags.
+//!
+//! Screens made of panels can be drawn through [`TextBufferView`](struct.TextBufferView.html)s, which have their own
+//! coordinates and cursor, and clip everything written through them to their rectangle.
+//! Prefabricated panels can be kept in off-screen `TextBuffer`s and copied into place with
+//! [`blit`](struct.TextBuffer.html#method.blit).
+//!
+//! Frames, lines, circles and fills can be drawn with the cursor's style with the drawing methods of `TextBuffer`,
+//! ie. [`draw_box`](struct.TextBuffer.html#method.draw_box), which joins box-drawing characters into the correct junctions.
+//!
+//! Besides colors and shakiness, the cursor has [`TextAttributes`](struct.TextAttributes.html) (bold, italic, underline etc.)
+//! that are given to the characters it writes.
+//! The cursor isn't drawn by default, but it can be shown as a blinking block, underline or bar for text entry,
+//! see [`set_cursor_visible`](struct.TextBuffer.html#method.set_cursor_visible).
+//! Its state can be saved and restored with [`push_cursor`](struct.TextBuffer.html#method.push_cursor) and
+//! [`pop_cursor`](struct.TextBuffer.html#method.pop_cursor), ie. to write a status line without losing the place in the text.
+//!
 //! ### Example usage of `TextBuffer`:
 //! ```no_run
 //! use glerminal::terminal::TerminalBuilder;
@@ -69,15 +94,32 @@
 //! terminal.flush(&mut text_buffer);
 //! ```
 
+mod attributes;
+mod blit;
+mod color;
+mod cursor;
+mod draw;
+pub mod layout;
 pub mod parser;
+mod region;
+mod scrollback;
+mod view;
+mod width;
 
 use renderer::textbuffermesh::TextBufferMesh;
 use renderer::backgroundmesh::BackgroundMesh;
+use renderer::cursormesh::CursorMesh;
 use font::Font;
-use terminal::Terminal;
-
-/// Represents a color with values from 0.0 to 1.0 (red, green, blue, alpha)
-pub type Color = [f32; 4];
+use terminal::{FontHandle, Terminal};
+use self::cursor::CursorDisplay;
+use self::scrollback::Scrollback;
+use self::width::MAX_COMBINING;
+pub use self::attributes::TextAttributes;
+pub use self::blit::BlitOptions;
+pub use self::color::Color;
+pub use self::cursor::{CursorBoundsPolicy, CursorStyle};
+pub use self::draw::BoxStyle;
+pub use self::view::TextBufferView;
 
 /// Represents a single character in a [`TextBuffer`](struct.TextBuffer.html)
 #[derive(Clone, Copy)]
@@ -86,6 +128,11 @@ pub struct TermCharacter {
     fg_color: Color,
     bg_color: Color,
     shakiness: f32,
+    attributes: TextAttributes,
+    /// The amount of cells the character takes; 0 for the continuation of a wide character
+    width: u8,
+    /// The combining characters attached to the character, the unused ones being `'\0'`
+    combining: [char; MAX_COMBINING],
 }
 
 impl TermCharacter {
@@ -100,9 +147,17 @@ impl TermCharacter {
             fg_color,
             bg_color,
             shakiness,
+            attributes: TextAttributes::NONE,
+            width: 1,
+            combining: ['\0'; MAX_COMBINING],
         }
     }
 
+    /// Returns an empty cell: a space with transparent colors and no shakiness or attributes.
+    pub(crate) fn empty() -> TermCharacter {
+        TermCharacter::new(' ', Color::TRANSPARENT, Color::TRANSPARENT, 0.0)
+    }
+
     /// Gets the char in the TermCharacter
     pub fn get_char(&self) -> char {
         self.character
@@ -124,12 +179,53 @@ impl TermCharacter {
     }
 }
 
-struct TermCursor {
+/// The width of a tab stop in characters; `'\t'` moves the cursor to the next multiple of this.
+const TAB_WIDTH: i32 = 4;
+
+/// Decides what happens when the cursor moves past the last row of a [`TextBuffer`](struct.TextBuffer.html),
+/// ie. when writing past the end of the last row or writing a newline on the last row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowMode {
+    /// The cursor moves back to the first row, and writing continues from the top (default).
+    Wrap,
+    /// All rows are shifted up by one, the last row is cleared and writing continues on it, like in a console.
+    /// The first row is moved into the scrollback history, see [`TextBuffer::set_scrollback_limit`](struct.TextBuffer.html#method.set_scrollback_limit).
+    Scroll,
+    /// The cursor stays at the end of the last row and further characters are discarded until the cursor is moved.
+    Clip,
+}
+
+impl Default for OverflowMode {
+    fn default() -> OverflowMode {
+        OverflowMode::Wrap
+    }
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct TermCursor {
     x: i32,
     y: i32,
     foreground_color: Color,
     background_color: Color,
     shakiness: f32,
+    attributes: TextAttributes,
+    /// Whether the cursor has moved past the last row in `OverflowMode::Clip`
+    clipped: bool,
+}
+
+impl TermCursor {
+    /// Returns the given character with the cursor's style
+    fn character(&self, character: char) -> TermCharacter {
+        TermCharacter {
+            character,
+            fg_color: self.foreground_color,
+            bg_color: self.background_color,
+            shakiness: self.shakiness,
+            attributes: self.attributes,
+            width: 1,
+            combining: ['\0'; MAX_COMBINING],
+        }
+    }
 }
 
 /// Represents the text buffer of the terminal; contains the "grid of [`TermCharacters`](struct.TermCharacter.html)" that will be drawn.
@@ -141,13 +237,32 @@ pub struct TextBuffer {
     pub(crate) width: i32,
     pub(crate) mesh: Option<TextBufferMesh>,
     pub(crate) background_mesh: Option<BackgroundMesh>,
+    pub(crate) cursor_mesh: Option<CursorMesh>,
     pub(crate) aspect_ratio: f32,
+    pub(crate) font_handle: FontHandle,
+    overflow_mode: OverflowMode,
+    scrollback: Scrollback,
+    fit_to_window: bool,
     cursor: TermCursor,
+    cursor_display: CursorDisplay,
+    cursor_bounds_policy: CursorBoundsPolicy,
+    cursor_stack: Vec<TermCursor>,
 }
 
 impl TextBuffer {
     /// Creates a new text buffer with the given dimensions (width in characters, height in characters)
+    /// that uses the default font of the terminal.
     pub fn new(terminal: &Terminal, dimensions: (i32, i32)) -> Result<TextBuffer, String> {
+        TextBuffer::new_with_font(terminal, dimensions, FontHandle::default())
+    }
+
+    /// Creates a new text buffer with the given dimensions (width in characters, height in characters)
+    /// that uses the font with the given handle, see [`Terminal::add_font`](../terminal/struct.Terminal.html#method.add_font).
+    pub fn new_with_font(
+        terminal: &Terminal,
+        dimensions: (i32, i32),
+        font_handle: FontHandle,
+    ) -> Result<TextBuffer, String> {
         let (width, height) = dimensions;
 
         if width <= 0 || height <= 0 {
@@ -156,20 +271,34 @@ impl TextBuffer {
             );
         }
 
-        let chars =
-            vec![TermCharacter::new(' ', [0.0; 4], [0.0; 4], 0.0); (width * height) as usize];
+        if !terminal.has_font(font_handle) {
+            return Err("TextBuffer font handle is not registered with the terminal".to_owned());
+        }
+
+        let font = terminal.get_font_by_handle(font_handle);
+        let chars = vec![TermCharacter::empty(); (width * height) as usize];
         let mesh;
         let background_mesh;
+        let cursor_mesh;
         if terminal.headless {
             mesh = None;
             background_mesh = None;
+            cursor_mesh = None;
         } else {
-            mesh = Some(TextBufferMesh::new(terminal.get_program(), dimensions, &terminal.font));
-            background_mesh = Some(BackgroundMesh::new(terminal.get_background_program(), dimensions));
+            mesh = Some(TextBufferMesh::new(
+                terminal.get_program(font_handle),
+                dimensions,
+                &font,
+            ));
+            background_mesh = Some(BackgroundMesh::new(
+                terminal.get_background_program(),
+                dimensions,
+            ));
+            cursor_mesh = Some(CursorMesh::new(terminal.get_background_program()));
         }
 
-        let true_height = height * terminal.font.line_height as i32;
-        let true_width = width * terminal.font.size as i32;
+        let true_height = height * font.line_height as i32;
+        let true_width = width * font.size as i32;
 
         Ok(TextBuffer {
             chars,
@@ -177,22 +306,151 @@ impl TextBuffer {
             width,
             mesh,
             background_mesh,
+            cursor_mesh,
+            fit_to_window: false,
             cursor: TermCursor {
                 x: 0,
                 y: 0,
-                foreground_color: [1.0; 4],
-                background_color: [0.0; 4],
+                foreground_color: Color::BRIGHT_WHITE,
+                background_color: Color::TRANSPARENT,
                 shakiness: 0.0,
+                attributes: TextAttributes::NONE,
+                clipped: false,
             },
+            cursor_display: CursorDisplay::new(),
+            cursor_bounds_policy: CursorBoundsPolicy::default(),
+            cursor_stack: Vec::new(),
+            overflow_mode: OverflowMode::default(),
+            scrollback: Scrollback::new(),
             aspect_ratio: true_width as f32 / true_height as f32,
+            font_handle,
         })
     }
 
+    /// Resizes the text buffer to the given dimensions (width in characters, height in characters),
+    /// ie. when the window is resized. Example:
+    ///
+    /// ```
+    /// use glerminal::terminal::TerminalBuilder;
+    /// use glerminal::text_buffer::TextBuffer;
+    ///
+    /// let terminal = TerminalBuilder::new().with_headless(true).build();
+    /// let mut text_buffer = TextBuffer::new(&terminal, (80, 24)).unwrap();
+    /// text_buffer.write("Hello!");
+    ///
+    /// text_buffer.resize(&terminal, (120, 36)).unwrap();
+    /// assert_eq!(text_buffer.get_character(0, 0).get_char(), 'H');
+    /// ```
+    ///
+    /// The existing characters stay in their positions; characters that are outside the new dimensions are cropped away,
+    /// and new cells are empty. Rows in the scrollback history are cropped and padded the same way.
+    /// The cursor is moved inside the new dimensions if necessary. The text buffer is drawn empty until it's flushed.
+    ///
+    /// Returns an error and leaves the text buffer as it is if either of the new dimensions is below 1.
+    pub fn resize(&mut self, terminal: &Terminal, dimensions: (i32, i32)) -> Result<(), String> {
+        let (width, height) = dimensions;
+        if width <= 0 || height <= 0 {
+            return Err(
+                "TextBuffer dimensions are erronous; either width or height is below 1".to_owned(),
+            );
+        }
+        self.set_dimensions(terminal, dimensions);
+        Ok(())
+    }
+
+    /// Resizes the text buffer like [`resize`](#method.resize), when the dimensions are known to be at least 1.
+    pub(crate) fn set_dimensions(&mut self, terminal: &Terminal, dimensions: (i32, i32)) {
+        let (width, height) = dimensions;
+        let mut chars = vec![TermCharacter::empty(); (width * height) as usize];
+        for y in 0..height.min(self.height) {
+            for x in 0..width.min(self.width) {
+                chars[(y * width + x) as usize] = self.get_character(x, y);
+            }
+            // A wide character whose continuation was cropped away doesn't fit anymore
+            let last = (y * width + width - 1) as usize;
+            if chars[last].width == 2 {
+                chars[last] = chars[last].emptied();
+            }
+        }
+        self.chars = chars;
+        self.width = width;
+        self.height = height;
+        self.resize_scrollback(width as usize);
+
+        if self.cursor.x >= width || self.cursor.y >= height {
+            self.cursor.x = self.cursor.x.min(width - 1);
+            self.cursor.y = self.cursor.y.min(height - 1);
+            self.cursor.clipped = false;
+        }
+
+        self.update_aspect_ratio(&terminal.get_font_by_handle(self.font_handle));
+
+        if let (&Some(ref mesh), &Some(ref background_mesh)) = (&self.mesh, &self.background_mesh) {
+            mesh.resize(dimensions);
+            background_mesh.resize(dimensions);
+        }
+    }
+
+    /// Sets whether the text buffer is resized to fill the window (default false).
+    ///
+    /// When enabled, the text buffer is resized to [as many cells as fit](../terminal/struct.Terminal.html#method.get_grid_size)
+    /// into the window whenever it's flushed, and drawn with each cell at exactly the size of its font times the
+    /// [cell scale](../terminal/struct.Terminal.html#method.set_cell_scale), centered in the window.
+    /// The contents are kept the same way as with [`resize`](#method.resize).
+    pub fn set_fit_to_window(&mut self, fit_to_window: bool) {
+        self.fit_to_window = fit_to_window;
+    }
+
+    /// Returns whether the text buffer is resized to fill the window, see [`set_fit_to_window`](#method.set_fit_to_window).
+    pub fn is_fit_to_window(&self) -> bool {
+        self.fit_to_window
+    }
+
+    /// Creates a view into the rectangle at the given position (x, y) with the given dimensions (width, height),
+    /// see [`TextBufferView`](struct.TextBufferView.html).
+    pub fn view<'a>(
+        &'a mut self,
+        position: (i32, i32),
+        dimensions: (i32, i32),
+    ) -> TextBufferView<'a> {
+        let clip = (0, 0, self.width, self.height);
+        let cursor = self.cursor;
+        TextBufferView::new(self, position, dimensions, clip, cursor)
+    }
+
+    /// Returns the handle of the font the text buffer uses.
+    pub fn get_font_handle(&self) -> FontHandle {
+        self.font_handle
+    }
+
+    /// Recalculates the aspect ratio of the whole text buffer in pixels, from the dimensions and the size of the font.
+    pub(crate) fn update_aspect_ratio(&mut self, font: &Font) {
+        let true_height = self.height * font.line_height as i32;
+        let true_width = self.width * font.size as i32;
+        self.aspect_ratio = true_width as f32 / true_height as f32;
+    }
+
     pub(crate) fn swap_buffers(&self, font: &Font) {
         if let (&Some(ref mesh), &Some(ref background_mesh)) = (&self.mesh, &self.background_mesh) {
             mesh.update(&self, font);
             background_mesh.update(&self);
         }
+        if let Some(ref cursor_mesh) = self.cursor_mesh {
+            cursor_mesh.update(self);
+        }
+    }
+
+    /// Returns the chars that are in view, see [`get_visible_character`](#method.get_visible_character).
+    pub(crate) fn visible_chars(&self) -> Vec<char> {
+        let mut chars = Vec::with_capacity(self.chars.len());
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let character = self.get_visible_character(x, y);
+                chars.push(character.get_char());
+                chars.extend(character.get_combining_chars());
+            }
+        }
+        chars
     }
 
     pub(crate) fn out_of_bounds(&self, x: i32, y: i32) -> bool {
@@ -206,21 +464,28 @@ impl TextBuffer {
 
     /// Clears the screen (makes every character empty and resets their style)
     pub fn clear(&mut self) {
-        self.chars = vec![
-            TermCharacter::new(' ', [0.0; 4], [0.0; 4], 0.0);
-            (self.width * self.height) as usize
-        ];
+        self.chars = vec![TermCharacter::empty(); (self.width * self.height) as usize];
     }
 
     /// Puts a character to the current position of the cursor with the cursor's style
+    ///
+    /// The following control characters move the cursor instead of being drawn:
+    /// - `'\n'` moves the cursor to the start of the next row
+    /// - `'\r'` moves the cursor to the start of the current row
+    /// - `'\t'` moves the cursor to the next tab stop (every 4 characters)
+    /// - `'\u{8}'` (backspace) moves the cursor back by one character and clears that character
+    ///
+    /// Wide characters, like most CJK characters, take two cells: the second cell is left empty with a
+    /// [width](struct.TermCharacter.html#method.get_width) of 0, and if the character doesn't fit on the current row,
+    /// it's put on the next one. Combining characters (ie. accents) are attached to the previous character.
+    ///
+    /// What happens when the cursor moves past the last row depends on the [`OverflowMode`](enum.OverflowMode.html),
+    /// see [`set_overflow_mode`](#method.set_overflow_mode).
     pub fn put_char(&mut self, character: char) {
-        self.chars[(self.cursor.y * self.width + self.cursor.x) as usize] = TermCharacter::new(
-            character,
-            self.cursor.foreground_color,
-            self.cursor.background_color,
-            self.cursor.shakiness,
-        );
-        self.move_cursor_by(1);
+        let mut cursor = self.cursor;
+        let region = self.region();
+        self.put_char_in(&mut cursor, region, character);
+        self.cursor = cursor;
     }
 
     /// Puts the given text the same way as put_char
@@ -232,13 +497,13 @@ impl TextBuffer {
     }
 
     /// Changes the foreground color for the cursor
-    pub fn change_cursor_fg_color(&mut self, color: Color) {
-        self.cursor.foreground_color = color;
+    pub fn change_cursor_fg_color<T: Into<Color>>(&mut self, color: T) {
+        self.cursor.foreground_color = color.into();
     }
 
     /// Changes the background color of the cursor
-    pub fn change_cursor_bg_color(&mut self, color: Color) {
-        self.cursor.background_color = color;
+    pub fn change_cursor_bg_color<T: Into<Color>>(&mut self, color: T) {
+        self.cursor.background_color = color.into();
     }
 
     /// Returns the current foreground color of the cursor
@@ -261,30 +526,87 @@ impl TextBuffer {
         self.cursor.shakiness
     }
 
-    /// Moves the cursor to a specified location in the terminal. If the location does not exist, nothing happens.
+    /// Changes the text attributes of the cursor, see [`TextAttributes`](struct.TextAttributes.html)
+    pub fn change_cursor_attributes(&mut self, attributes: TextAttributes) {
+        self.cursor.attributes = attributes;
+    }
+
+    /// Gets the current text attributes of the cursor
+    pub fn get_cursor_attributes(&self) -> TextAttributes {
+        self.cursor.attributes
+    }
+
+    /// Moves the cursor to a specified location in the terminal. If the location does not exist, what happens depends on
+    /// the [`CursorBoundsPolicy`](enum.CursorBoundsPolicy.html): by default nothing happens.
+    ///
+    /// Panics if the location does not exist and the policy is `CursorBoundsPolicy::Error`,
+    /// see [`try_move_cursor`](#method.try_move_cursor) for a non-panicking version.
     pub fn move_cursor(&mut self, x: i32, y: i32) {
-        if !self.out_of_bounds(x, y) {
-            self.cursor.x = x;
-            self.cursor.y = y;
+        if let Err(error) = self.try_move_cursor(x, y) {
+            panic!("Failed to move cursor: {}", error);
         }
     }
 
+    /// Moves the cursor to a specified location in the terminal, returning an error if the location does not exist and
+    /// the [`CursorBoundsPolicy`](enum.CursorBoundsPolicy.html) is `CursorBoundsPolicy::Error`.
+    pub fn try_move_cursor(&mut self, x: i32, y: i32) -> Result<(), String> {
+        let (x, y) = if self.out_of_bounds(x, y) {
+            match self.cursor_bounds_policy {
+                CursorBoundsPolicy::Ignore => return Ok(()),
+                CursorBoundsPolicy::Clamp => {
+                    (x.max(0).min(self.width - 1), y.max(0).min(self.height - 1))
+                }
+                CursorBoundsPolicy::Error => {
+                    return Err(format!(
+                        "position ({}, {}) is outside the TextBuffer dimensions ({}, {})",
+                        x, y, self.width, self.height
+                    ))
+                }
+            }
+        } else {
+            (x, y)
+        };
+        self.cursor.x = x;
+        self.cursor.y = y;
+        self.cursor.clipped = false;
+        Ok(())
+    }
+
+    /// Sets what happens when the cursor moves past the last row, see [`OverflowMode`](enum.OverflowMode.html). Example:
+    ///
+    /// ```
+    /// use glerminal::terminal::TerminalBuilder;
+    /// use glerminal::text_buffer::{OverflowMode, TextBuffer};
+    ///
+    /// let terminal = TerminalBuilder::new().with_headless(true).build();
+    /// let mut log = TextBuffer::new(&terminal, (40, 10)).unwrap();
+    /// log.set_overflow_mode(OverflowMode::Scroll);
+    /// for i in 0..20 {
+    ///     log.write(format!("Line {}\n", i));
+    /// }
+    /// // The last line is left empty for the next line of the log
+    /// assert_eq!(log.get_character(6, 8).get_char(), '9');
+    /// ```
+    pub fn set_overflow_mode(&mut self, overflow_mode: OverflowMode) {
+        self.overflow_mode = overflow_mode;
+        self.cursor.clipped = false;
+    }
+
+    /// Returns what happens when the cursor moves past the last row, see [`set_overflow_mode`](#method.set_overflow_mode).
+    pub fn get_overflow_mode(&self) -> OverflowMode {
+        self.overflow_mode
+    }
+
     /// Returns the current position of the cursor
     pub fn get_cursor_position(&self) -> (i32, i32) {
         (self.cursor.x, self.cursor.y)
     }
 
-    fn move_cursor_by(&mut self, amount: i32) {
-        let new_pos = self.cursor.x + amount;
-        if new_pos >= 0 {
-            self.cursor.x += amount;
-            if self.cursor.x >= self.width {
-                self.cursor.x = 0;
-                self.cursor.y += 1;
-                if self.cursor.y >= self.height {
-                    self.cursor.y = 0;
-                }
-            }
-        }
+    /// Shifts every row up by one, moving the first row into the scrollback history and clearing the last row.
+    fn scroll_up(&mut self) {
+        let width = self.width as usize;
+        let row = self.chars.drain(0..width).collect();
+        self.scrollback.push(row);
+        self.chars.extend(vec![TermCharacter::empty(); width]);
     }
 }
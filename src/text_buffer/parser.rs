@@ -3,7 +3,8 @@
 use std::collections::HashMap;
 
 use regex::Regex;
-use super::{Color, TextBuffer};
+use super::region::Region;
+use super::{Color, TermCharacter, TermCursor, TextBuffer, TextBufferView};
 
 /// Represents a parser, that is able to read given texts and use [`TextBuffer`](struct.TextBuffer.html) accordingly, to write text and styles matching to the text.
 ///
@@ -21,8 +22,8 @@ impl Parser {
     }
 
     /// Adds a color to the parser to use later.
-    pub fn add_color<T: Into<String>>(&mut self, color_str: T, color: Color) {
-        self.colors.insert(color_str.into(), color);
+    pub fn add_color<T: Into<String>, C: Into<Color>>(&mut self, color_str: T, color: C) {
+        self.colors.insert(color_str.into(), color.into());
     }
 
     /// Parses the given text and makes it look according to the parsed text.
@@ -56,25 +57,70 @@ impl Parser {
     /// terminal.flush(&mut text_buffer);
     /// ```
     pub fn write<T: Into<String>>(&self, text_buffer: &mut TextBuffer, text: T) {
-        let text = text.into();
+        let mut cursor = text_buffer.cursor;
+        let region = text_buffer.region();
+        self.write_in(text_buffer, &mut cursor, region, &text.into());
+        text_buffer.cursor = cursor;
+    }
+
+    /// Parses the given text and writes it into the [`TextBufferView`](../struct.TextBufferView.html) the same way as
+    /// [`write`](#method.write) writes it into a `TextBuffer`, clipping it to the view.
+    pub fn write_to_view<T: Into<String>>(&self, view: &mut TextBufferView, text: T) {
+        let (text_buffer, cursor, region) = view.parts_mut();
+        self.write_in(text_buffer, cursor, region, &text.into());
+    }
+
+    /// Parses the given text and writes it with the cursor into the region, restoring the cursor's colors afterwards.
+    fn write_in(
+        &self,
+        text_buffer: &mut TextBuffer,
+        cursor: &mut TermCursor,
+        region: Region,
+        text: &str,
+    ) {
+        let default_fg = cursor.foreground_color;
+        let default_bg = cursor.background_color;
+
+        let (characters, (_, _, shakiness)) =
+            self.parse(text, (default_fg, default_bg, cursor.shakiness));
+        for character in characters {
+            cursor.foreground_color = character.fg_color;
+            cursor.background_color = character.bg_color;
+            cursor.shakiness = character.shakiness;
+            text_buffer.put_char_in(cursor, region, character.character);
+        }
+
+        cursor.foreground_color = default_fg;
+        cursor.background_color = default_bg;
+        cursor.shakiness = shakiness;
+    }
 
-        let default_fg = text_buffer.get_cursor_fg_color();
-        let default_bg = text_buffer.get_cursor_bg_color();
-        let default_shakiness = text_buffer.get_cursor_shakiness();
+    /// Parses the given text into styled characters, starting with the given style (fg color, bg color, shakiness).
+    /// Returns the characters and the style at the end of the text.
+    pub(crate) fn parse(
+        &self,
+        text: &str,
+        default_style: (Color, Color, f32),
+    ) -> (Vec<TermCharacter>, (Color, Color, f32)) {
+        let (default_fg, default_bg, default_shakiness) = default_style;
+        let (mut fg, mut bg, mut shakiness) = default_style;
+        let mut characters = Vec::new();
 
         let regex = Regex::new(r"\[(/)?((fg|bg|shake)(=([A-z]+|\d+(\.\d+)?))?)\]").unwrap();
-        let mut parts = regex.split(&text);
+        let mut parts = regex.split(text);
 
-        for capture in regex.captures_iter(&text) {
-            text_buffer.write(parts.next().unwrap());
+        for capture in regex.captures_iter(text) {
+            for character in parts.next().unwrap().chars() {
+                characters.push(TermCharacter::new(character, fg, bg, shakiness));
+            }
             if let Some(target) = capture.get(3) {
                 if let Some(_) = capture.get(1) {
                     if target.as_str() == "shake" {
-                        text_buffer.change_cursor_shakiness(default_shakiness);
+                        shakiness = default_shakiness;
                     } else if target.as_str() == "fg" {
-                        text_buffer.change_cursor_fg_color(default_fg);
+                        fg = default_fg;
                     } else if target.as_str() == "bg" {
-                        text_buffer.change_cursor_bg_color(default_bg);
+                        bg = default_bg;
                     }
                 }
                 if let Some(value) = capture.get(5) {
@@ -83,13 +129,13 @@ impl Parser {
                             Ok(val) => val,
                             Err(_) => panic!("Failed to parse shake-number"),
                         };
-                        text_buffer.change_cursor_shakiness(value);
+                        shakiness = value;
                     } else {
                         if let Some(color) = self.colors.get(value.as_str()) {
                             if target.as_str() == "fg" {
-                                text_buffer.change_cursor_fg_color(*color);
+                                fg = *color;
                             } else {
-                                text_buffer.change_cursor_bg_color(*color);
+                                bg = *color;
                             }
                         }
                     }
@@ -97,11 +143,12 @@ impl Parser {
             }
         }
         if let Some(last_part) = parts.next() {
-            text_buffer.write(last_part);
+            for character in last_part.chars() {
+                characters.push(TermCharacter::new(character, fg, bg, shakiness));
+            }
         }
 
-        text_buffer.change_cursor_fg_color(default_fg);
-        text_buffer.change_cursor_bg_color(default_bg);
+        (characters, (fg, bg, shakiness))
     }
 
     /// Gets the color specified, not compiled in a non-testing environment.
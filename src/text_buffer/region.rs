@@ -0,0 +1,175 @@
+//! Writing with a cursor into a rectangle of a `TextBuffer`, shared by `TextBuffer` and `TextBufferView`.
+
+use super::{width, OverflowMode, TermCharacter, TermCursor, TextBuffer, TAB_WIDTH};
+
+/// The rectangle of a `TextBuffer` a cursor writes into. The cursor's position is relative to the rectangle.
+#[derive(Clone, Copy)]
+pub(crate) struct Region {
+    /// The position of the top-left corner in the text buffer
+    pub(crate) position: (i32, i32),
+    pub(crate) dimensions: (i32, i32),
+    /// The absolute area that can be written to (left, top, right, bottom), right and bottom being exclusive
+    pub(crate) clip: (i32, i32, i32, i32),
+    pub(crate) overflow_mode: OverflowMode,
+    /// Whether the rows that scroll off the top are moved into the scrollback history; only for the whole text buffer
+    pub(crate) scrollback: bool,
+}
+
+impl Region {
+    /// Returns whether the region has no cells, in which case its cursor can't write anything.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.dimensions.0 == 0 || self.dimensions.1 == 0
+    }
+
+    /// Returns the position in the text buffer of the given position of the region, if it isn't clipped away.
+    pub(crate) fn to_absolute(self, x: i32, y: i32) -> Option<(i32, i32)> {
+        let (x, y) = (self.position.0 + x, self.position.1 + y);
+        let (left, top, right, bottom) = self.clip;
+        if x < left || y < top || x >= right || y >= bottom {
+            None
+        } else {
+            Some((x, y))
+        }
+    }
+}
+
+impl TextBuffer {
+    /// Returns the region of the whole text buffer, which its own cursor writes into.
+    pub(crate) fn region(&self) -> Region {
+        Region {
+            position: (0, 0),
+            dimensions: (self.width, self.height),
+            clip: (0, 0, self.width, self.height),
+            overflow_mode: self.overflow_mode,
+            scrollback: true,
+        }
+    }
+
+    /// Gets the character in the given position of the region, or None if it's clipped away.
+    pub(crate) fn get_cell_in(&self, region: Region, x: i32, y: i32) -> Option<TermCharacter> {
+        region
+            .to_absolute(x, y)
+            .map(|(x, y)| self.get_character(x, y))
+    }
+
+    /// Puts the character to the given position of the region like [`set_cell`](#method.set_cell),
+    /// unless the position is clipped away.
+    pub(crate) fn set_cell_in(&mut self, region: Region, x: i32, y: i32, character: TermCharacter) {
+        if let Some((x, y)) = region.to_absolute(x, y) {
+            self.set_cell(x, y, character);
+        }
+    }
+
+    /// Puts a character to the position of the cursor in the region, see [`put_char`](#method.put_char).
+    pub(crate) fn put_char_in(&mut self, cursor: &mut TermCursor, region: Region, character: char) {
+        match character {
+            '\n' => self.new_line_in(cursor, region),
+            '\r' => {
+                cursor.x = 0;
+                cursor.clipped = region.is_empty();
+            }
+            '\t' => {
+                let amount = TAB_WIDTH - cursor.x % TAB_WIDTH;
+                self.move_cursor_in(cursor, region, amount);
+            }
+            '\u{8}' => self.backspace_in(cursor, region),
+            _ => match width::char_width(character) {
+                0 => self.put_combining_char(cursor, region, character),
+                _ if cursor.clipped => {}
+                2 if region.dimensions.0 >= 2 => self.put_wide_char(cursor, region, character),
+                _ => {
+                    let (x, y) = (cursor.x, cursor.y);
+                    self.set_cell_in(region, x, y, cursor.character(character));
+                    self.move_cursor_in(cursor, region, 1);
+                }
+            },
+        }
+    }
+
+    /// Moves the cursor right by the given amount, moving to the next row if it goes past the end of the row.
+    pub(crate) fn move_cursor_in(&mut self, cursor: &mut TermCursor, region: Region, amount: i32) {
+        let new_pos = cursor.x + amount;
+        if new_pos >= 0 {
+            if new_pos >= region.dimensions.0 {
+                self.new_line_in(cursor, region);
+            } else {
+                cursor.x = new_pos;
+            }
+        }
+    }
+
+    /// Moves the cursor to the start of the next row, or overflows according to the region's `OverflowMode`.
+    pub(crate) fn new_line_in(&mut self, cursor: &mut TermCursor, region: Region) {
+        if cursor.clipped {
+            return;
+        }
+        if cursor.y + 1 < region.dimensions.1 {
+            cursor.x = 0;
+            cursor.y += 1;
+            return;
+        }
+        match region.overflow_mode {
+            OverflowMode::Wrap => {
+                cursor.x = 0;
+                cursor.y = 0;
+            }
+            OverflowMode::Scroll => {
+                cursor.x = 0;
+                self.scroll_up_in(region);
+            }
+            OverflowMode::Clip => cursor.clipped = true,
+        }
+    }
+
+    /// Moves the cursor back by one character and clears that character.
+    fn backspace_in(&mut self, cursor: &mut TermCursor, region: Region) {
+        if cursor.clipped {
+            if region.is_empty() {
+                return;
+            }
+            // The cursor is still on the last character, which hasn't been erased yet
+            cursor.clipped = false;
+        } else if cursor.x > 0 {
+            cursor.x -= 1;
+        } else if cursor.y > 0 {
+            cursor.x = region.dimensions.0 - 1;
+            cursor.y -= 1;
+        } else {
+            return;
+        }
+        // Wide characters are erased as a whole
+        let (x, y) = (cursor.x, cursor.y);
+        if x > 0 && self.get_cell_in(region, x, y).map(|c| c.width) == Some(0) {
+            cursor.x -= 1;
+        }
+        let x = cursor.x;
+        self.set_cell_in(region, x, y, TermCharacter::empty());
+    }
+
+    /// Shifts every row of the region up by one, clearing the last row. The first row is moved into the scrollback
+    /// history if the region keeps one, and discarded otherwise.
+    fn scroll_up_in(&mut self, region: Region) {
+        if region.scrollback {
+            self.scroll_up();
+            return;
+        }
+        let (left, top, right, bottom) = region.clip;
+        if left >= right || top >= bottom {
+            return;
+        }
+        // Wide characters that cross the sides would be torn apart
+        self.split_wide_chars(left, top, bottom);
+        self.split_wide_chars(right, top, bottom);
+        let width = self.width as usize;
+        for y in top..bottom {
+            for x in left..right {
+                let index = (y * self.width + x) as usize;
+                self.chars[index] = if y + 1 < bottom {
+                    self.chars[index + width]
+                } else {
+                    TermCharacter::empty()
+                };
+            }
+        }
+    }
+}
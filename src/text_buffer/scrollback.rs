@@ -0,0 +1,142 @@
+//! Scrollback history of a `TextBuffer`, which keeps the rows that scroll off the top of the buffer.
+
+use std::collections::VecDeque;
+
+use super::{TermCharacter, TextBuffer};
+
+/// The rows that have scrolled off the top of a `TextBuffer`, and the part of them that is in view.
+pub(crate) struct Scrollback {
+    /// The rows, oldest first
+    rows: VecDeque<Vec<TermCharacter>>,
+    limit: usize,
+    /// How many rows above the live bottom the view is
+    offset: usize,
+}
+
+impl Scrollback {
+    pub(crate) fn new() -> Scrollback {
+        Scrollback {
+            rows: VecDeque::new(),
+            limit: 0,
+            offset: 0,
+        }
+    }
+
+    /// Adds a row that scrolled off the top, dropping the oldest rows if there are more than the limit.
+    pub(crate) fn push(&mut self, row: Vec<TermCharacter>) {
+        if self.limit == 0 {
+            return;
+        }
+        self.rows.push_back(row);
+        while self.rows.len() > self.limit {
+            self.rows.pop_front();
+        }
+        if self.offset > 0 {
+            // Keep the view on the same rows while new rows come in
+            self.offset = (self.offset + 1).min(self.rows.len());
+        }
+    }
+}
+
+impl TextBuffer {
+    /// Crops or pads the rows of the scrollback history to the given width.
+    pub(crate) fn resize_scrollback(&mut self, width: usize) {
+        for row in self.scrollback.rows.iter_mut() {
+            row.resize(width, TermCharacter::empty());
+        }
+    }
+
+    /// Sets how many rows that scroll off the top of the buffer (see [`OverflowMode::Scroll`](enum.OverflowMode.html))
+    /// are kept in the scrollback history. Defaults to 0, which disables the history. Example:
+    ///
+    /// ```
+    /// use glerminal::terminal::TerminalBuilder;
+    /// use glerminal::text_buffer::{OverflowMode, TextBuffer};
+    ///
+    /// let terminal = TerminalBuilder::new().with_headless(true).build();
+    /// let mut console = TextBuffer::new(&terminal, (40, 10)).unwrap();
+    /// console.set_overflow_mode(OverflowMode::Scroll);
+    /// console.set_scrollback_limit(1000);
+    /// for i in 0..20 {
+    ///     console.write(format!("Line {}\n", i));
+    /// }
+    ///
+    /// // Show the lines 0 - 9 again
+    /// console.scroll_view_up(11);
+    /// assert_eq!(console.get_visible_character(5, 0).get_char(), '0');
+    /// console.scroll_view_to_bottom();
+    /// ```
+    ///
+    /// The viewport of the history that is in view is drawn instead of the current contents of the buffer; writing to the
+    /// buffer still happens at the live bottom.
+    pub fn set_scrollback_limit(&mut self, limit: usize) {
+        let scrollback = &mut self.scrollback;
+        scrollback.limit = limit;
+        while scrollback.rows.len() > limit {
+            scrollback.rows.pop_front();
+        }
+        scrollback.offset = scrollback.offset.min(scrollback.rows.len());
+    }
+
+    /// Returns how many rows are kept in the scrollback history at most.
+    pub fn get_scrollback_limit(&self) -> usize {
+        self.scrollback.limit
+    }
+
+    /// Returns how many rows there currently are in the scrollback history.
+    pub fn get_scrollback_len(&self) -> usize {
+        self.scrollback.rows.len()
+    }
+
+    /// Removes every row from the scrollback history, and moves the view back to the live bottom.
+    pub fn clear_scrollback(&mut self) {
+        self.scrollback.rows.clear();
+        self.scrollback.offset = 0;
+    }
+
+    /// Scrolls the view up by the given amount of rows, towards older rows in the scrollback history.
+    pub fn scroll_view_up(&mut self, rows: usize) {
+        let scrollback = &mut self.scrollback;
+        scrollback.offset = (scrollback.offset + rows).min(scrollback.rows.len());
+    }
+
+    /// Scrolls the view down by the given amount of rows, towards the live bottom.
+    pub fn scroll_view_down(&mut self, rows: usize) {
+        let scrollback = &mut self.scrollback;
+        scrollback.offset = scrollback.offset.saturating_sub(rows);
+    }
+
+    /// Scrolls the view up by the height of the buffer.
+    pub fn scroll_view_page_up(&mut self) {
+        let height = self.height as usize;
+        self.scroll_view_up(height);
+    }
+
+    /// Scrolls the view down by the height of the buffer.
+    pub fn scroll_view_page_down(&mut self) {
+        let height = self.height as usize;
+        self.scroll_view_down(height);
+    }
+
+    /// Moves the view back to the live bottom, showing the current contents of the buffer.
+    pub fn scroll_view_to_bottom(&mut self) {
+        self.scrollback.offset = 0;
+    }
+
+    /// Returns how many rows above the live bottom the view is; 0 when the current contents of the buffer are in view.
+    pub fn get_scroll_offset(&self) -> usize {
+        self.scrollback.offset
+    }
+
+    /// Gets the TermCharacter in the given position of the view, which is what is drawn.
+    /// Unless the view has been scrolled up into the scrollback history, this is the same as `get_character`.
+    pub fn get_visible_character(&self, x: i32, y: i32) -> TermCharacter {
+        let scrollback = &self.scrollback;
+        let row = scrollback.rows.len() - scrollback.offset + y as usize;
+        if row < scrollback.rows.len() {
+            scrollback.rows[row][x as usize]
+        } else {
+            self.get_character(x, (row - scrollback.rows.len()) as i32)
+        }
+    }
+}
@@ -0,0 +1,204 @@
+//! Rectangular views into a `TextBuffer`, with their own coordinates and cursor.
+
+use super::region::Region;
+use super::{Color, OverflowMode, TermCharacter, TermCursor, TextAttributes, TextBuffer};
+
+/// A rectangle of a [`TextBuffer`](struct.TextBuffer.html) with its own local coordinates and cursor,
+/// created with [`TextBuffer::view`](struct.TextBuffer.html#method.view). Example:
+///
+/// ```
+/// use glerminal::terminal::TerminalBuilder;
+/// use glerminal::text_buffer::TextBuffer;
+///
+/// let terminal = TerminalBuilder::new().with_headless(true).build();
+/// let mut text_buffer = TextBuffer::new(&terminal, (80, 24)).unwrap();
+///
+/// {
+///     let mut panel = text_buffer.view((10, 5), (4, 2));
+///     panel.write("Hello, World!");
+/// }
+/// assert_eq!(text_buffer.get_character(10, 5).get_char(), 'H');
+/// assert_eq!(text_buffer.get_character(10, 6).get_char(), 'o');
+/// assert_eq!(text_buffer.get_character(14, 5).get_char(), ' ');
+/// ```
+///
+/// Writing, clearing and the [`Parser`](parser/struct.Parser.html#method.write_to_view) only touch the characters inside the
+/// rectangle, and the parts of the rectangle that are outside the text buffer (or outside the parent view, for views of views)
+/// are clipped away. The cursor starts at the top-left corner of the view with the style of the text buffer's cursor, and
+/// moving it does not move the text buffer's cursor.
+///
+/// Views don't keep a scrollback history, and their [`OverflowMode`](enum.OverflowMode.html) defaults to `Clip`.
+pub struct TextBufferView<'a> {
+    text_buffer: &'a mut TextBuffer,
+    region: Region,
+    cursor: TermCursor,
+}
+
+impl<'a> TextBufferView<'a> {
+    pub(crate) fn new(
+        text_buffer: &'a mut TextBuffer,
+        position: (i32, i32),
+        dimensions: (i32, i32),
+        clip: (i32, i32, i32, i32),
+        cursor: TermCursor,
+    ) -> TextBufferView<'a> {
+        let (width, height) = (dimensions.0.max(0), dimensions.1.max(0));
+        let (left, top, right, bottom) = clip;
+        let clip = (
+            left.max(position.0),
+            top.max(position.1),
+            right.min(position.0 + width),
+            bottom.min(position.1 + height),
+        );
+        TextBufferView {
+            text_buffer,
+            region: Region {
+                position,
+                dimensions: (width, height),
+                clip,
+                overflow_mode: OverflowMode::Clip,
+                scrollback: false,
+            },
+            cursor: TermCursor {
+                x: 0,
+                y: 0,
+                clipped: width == 0 || height == 0,
+                ..cursor
+            },
+        }
+    }
+
+    /// Creates a view into a rectangle of this view, at the given position (x, y) in this view's coordinates with the given
+    /// dimensions (width, height). The new view is clipped to this view.
+    pub fn view<'b>(
+        &'b mut self,
+        position: (i32, i32),
+        dimensions: (i32, i32),
+    ) -> TextBufferView<'b> {
+        let region = self.region;
+        let position = (
+            region.position.0 + position.0,
+            region.position.1 + position.1,
+        );
+        TextBufferView::new(
+            &mut *self.text_buffer,
+            position,
+            dimensions,
+            region.clip,
+            self.cursor,
+        )
+    }
+
+    /// Returns the position of the view in the text buffer.
+    pub fn get_position(&self) -> (i32, i32) {
+        self.region.position
+    }
+
+    /// Returns the dimensions of the view (width, height).
+    pub fn get_dimensions(&self) -> (i32, i32) {
+        self.region.dimensions
+    }
+
+    /// Gets the TermCharacter in the given position of the view, or None if the position is clipped away.
+    pub fn get_character(&self, x: i32, y: i32) -> Option<TermCharacter> {
+        self.text_buffer.get_cell_in(self.region, x, y)
+    }
+
+    /// Clears the view (makes every character in it empty and resets their style)
+    pub fn clear(&mut self) {
+        let (width, height) = self.region.dimensions;
+        for y in 0..height {
+            for x in 0..width {
+                self.text_buffer
+                    .set_cell_in(self.region, x, y, TermCharacter::empty());
+            }
+        }
+    }
+
+    /// Puts a character to the current position of the cursor with the cursor's style,
+    /// handling control characters like [`TextBuffer::put_char`](struct.TextBuffer.html#method.put_char).
+    pub fn put_char(&mut self, character: char) {
+        self.text_buffer
+            .put_char_in(&mut self.cursor, self.region, character);
+    }
+
+    /// Puts the given text the same way as put_char
+    pub fn write<T: Into<String>>(&mut self, text: T) {
+        let text = text.into();
+        for c in text.chars() {
+            self.put_char(c);
+        }
+    }
+
+    /// Changes the foreground color for the cursor
+    pub fn change_cursor_fg_color<T: Into<Color>>(&mut self, color: T) {
+        self.cursor.foreground_color = color.into();
+    }
+
+    /// Changes the background color of the cursor
+    pub fn change_cursor_bg_color<T: Into<Color>>(&mut self, color: T) {
+        self.cursor.background_color = color.into();
+    }
+
+    /// Returns the current foreground color of the cursor
+    pub fn get_cursor_fg_color(&self) -> Color {
+        self.cursor.foreground_color
+    }
+
+    /// Returns the current background color of the cursor
+    pub fn get_cursor_bg_color(&self) -> Color {
+        self.cursor.background_color
+    }
+
+    /// Changes the shakiness of the cursor
+    pub fn change_cursor_shakiness(&mut self, shakiness: f32) {
+        self.cursor.shakiness = shakiness;
+    }
+
+    /// Gets the current shakiness of the cursor
+    pub fn get_cursor_shakiness(&self) -> f32 {
+        self.cursor.shakiness
+    }
+
+    /// Changes the text attributes of the cursor
+    pub fn change_cursor_attributes(&mut self, attributes: TextAttributes) {
+        self.cursor.attributes = attributes;
+    }
+
+    /// Gets the current text attributes of the cursor
+    pub fn get_cursor_attributes(&self) -> TextAttributes {
+        self.cursor.attributes
+    }
+
+    /// Moves the cursor to a specified location in the view. If the location is outside the view, nothing happens.
+    pub fn move_cursor(&mut self, x: i32, y: i32) {
+        let (width, height) = self.region.dimensions;
+        if x >= 0 && y >= 0 && x < width && y < height {
+            self.cursor.x = x;
+            self.cursor.y = y;
+            self.cursor.clipped = false;
+        }
+    }
+
+    /// Returns the current position of the cursor in the view
+    pub fn get_cursor_position(&self) -> (i32, i32) {
+        (self.cursor.x, self.cursor.y)
+    }
+
+    /// Sets what happens when the cursor moves past the last row of the view, see [`OverflowMode`](enum.OverflowMode.html).
+    /// When scrolling, the rows that scroll off the top of the view are discarded.
+    pub fn set_overflow_mode(&mut self, overflow_mode: OverflowMode) {
+        self.region.overflow_mode = overflow_mode;
+        self.cursor.clipped = self.region.is_empty();
+    }
+
+    /// Returns what happens when the cursor moves past the last row of the view.
+    pub fn get_overflow_mode(&self) -> OverflowMode {
+        self.region.overflow_mode
+    }
+
+    /// Returns the text buffer, the cursor and the region of the view, for writing into it.
+    pub(crate) fn parts_mut(&mut self) -> (&mut TextBuffer, &mut TermCursor, Region) {
+        (&mut *self.text_buffer, &mut self.cursor, self.region)
+    }
+}
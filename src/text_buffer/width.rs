@@ -0,0 +1,208 @@
+//! Double-width characters and combining characters in a `TextBuffer`.
+
+use unicode_width::UnicodeWidthChar;
+
+use super::region::Region;
+use super::{TermCharacter, TermCursor, TextBuffer};
+
+/// The maximum amount of combining characters stored in a single cell; further ones are discarded.
+pub(crate) const MAX_COMBINING: usize = 3;
+
+/// Returns the amount of cells the character takes: 2 for wide characters (ie. CJK), 0 for combining characters
+/// and 1 for every other character. Control characters take a single cell, as grid fonts like CP437 have glyphs for them.
+pub(crate) fn char_width(character: char) -> usize {
+    if character.is_control() {
+        1
+    } else {
+        UnicodeWidthChar::width(character).unwrap_or(1)
+    }
+}
+
+/// Turns the characters into the cells they take: wide characters are followed by a continuation cell, and combining
+/// characters are attached to the character before them. If `wide` is false, wide characters take a single cell.
+pub(crate) fn to_cells(characters: Vec<TermCharacter>, wide: bool) -> Vec<TermCharacter> {
+    let mut cells: Vec<TermCharacter> = Vec::with_capacity(characters.len());
+    for character in characters {
+        match char_width(character.character) {
+            0 => {
+                // The mark belongs to the wide character, not its continuation
+                let index = match cells.iter().rposition(|cell| cell.width != 0) {
+                    Some(index) => index,
+                    None => continue,
+                };
+                cells[index].add_combining_char(character.character);
+            }
+            2 if wide => {
+                cells.push(TermCharacter {
+                    width: 2,
+                    ..character
+                });
+                cells.push(TermCharacter {
+                    character: ' ',
+                    width: 0,
+                    ..character
+                });
+            }
+            _ => cells.push(character),
+        }
+    }
+    cells
+}
+
+impl TermCharacter {
+    /// Gets the amount of cells the TermCharacter takes: 2 for wide characters, like most CJK characters, 1 for other
+    /// characters and 0 for the cell right of a wide character, which the wide character continues to.
+    pub fn get_width(&self) -> usize {
+        self.width as usize
+    }
+
+    /// Gets the char in the TermCharacter along with the combining characters (ie. accents) attached to it.
+    pub fn get_grapheme(&self) -> String {
+        let mut grapheme = String::new();
+        grapheme.push(self.character);
+        grapheme.extend(self.combining.iter().take_while(|&&mark| mark != '\0'));
+        grapheme
+    }
+
+    /// Returns the combining characters attached to the char.
+    pub(crate) fn get_combining_chars(&self) -> Vec<char> {
+        self.combining
+            .iter()
+            .cloned()
+            .take_while(|&mark| mark != '\0')
+            .collect()
+    }
+
+    /// Attaches the combining character to the char, if there is room for it.
+    fn add_combining_char(&mut self, mark: char) {
+        if let Some(slot) = self.combining.iter_mut().find(|slot| **slot == '\0') {
+            *slot = mark;
+        }
+    }
+
+    /// Returns the character as an empty cell, keeping its style
+    pub(crate) fn emptied(&self) -> TermCharacter {
+        TermCharacter {
+            character: ' ',
+            width: 1,
+            combining: ['\0'; MAX_COMBINING],
+            ..*self
+        }
+    }
+}
+
+impl TextBuffer {
+    /// Puts the character to the given position. If it replaces half of a wide character,
+    /// the other half is emptied too.
+    pub(crate) fn set_cell(&mut self, x: i32, y: i32, character: TermCharacter) {
+        let old = self.get_character(x, y);
+        if old.width == 2 && x + 1 < self.width {
+            let next = self.get_character(x + 1, y);
+            if next.width == 0 {
+                self.chars[(y * self.width + x + 1) as usize] = next.emptied();
+            }
+        } else if old.width == 0 && x > 0 {
+            let previous = self.get_character(x - 1, y);
+            if previous.width == 2 {
+                self.chars[(y * self.width + x - 1) as usize] = previous.emptied();
+            }
+        }
+        self.chars[(y * self.width + x) as usize] = character;
+    }
+
+    /// Empties the wide characters that are split by the left side of the given column, in the rows from top to bottom
+    /// (exclusive).
+    pub(crate) fn split_wide_chars(&mut self, x: i32, top: i32, bottom: i32) {
+        if x <= 0 || x >= self.width {
+            return;
+        }
+        for y in top.max(0)..bottom.min(self.height) {
+            let index = (y * self.width + x) as usize;
+            if self.chars[index].width == 0 && self.chars[index - 1].width == 2 {
+                self.chars[index - 1] = self.chars[index - 1].emptied();
+                self.chars[index] = self.chars[index].emptied();
+            }
+        }
+    }
+
+    /// Puts the cells into the row starting from the given position, leaving out the ones outside the text buffer.
+    /// Wide characters that end up with only one of their cells, in the row or in the cells, are emptied.
+    pub(crate) fn put_cells(&mut self, x: i32, y: i32, cells: &[TermCharacter]) {
+        let left = x.max(0);
+        let right = (x + cells.len() as i32).min(self.width);
+        if y < 0 || y >= self.height || left >= right {
+            return;
+        }
+        self.split_wide_chars(left, y, y + 1);
+        self.split_wide_chars(right, y, y + 1);
+        let start = (y * self.width) as usize;
+        for column in left..right {
+            self.chars[start + column as usize] = cells[(column - x) as usize];
+        }
+        for column in left as usize..right as usize {
+            let orphaned = match self.chars[start + column].width {
+                0 => column == 0 || self.chars[start + column - 1].width != 2,
+                2 => column + 1 >= self.width as usize || self.chars[start + column + 1].width != 0,
+                _ => false,
+            };
+            if orphaned {
+                self.chars[start + column] = self.chars[start + column].emptied();
+            }
+        }
+    }
+
+    /// Puts the wide character to the cursor and the cell right of it, moving to the next row first if it doesn't fit.
+    /// If only one of the cells is inside the region, only the background is drawn into it.
+    pub(crate) fn put_wide_char(
+        &mut self,
+        cursor: &mut TermCursor,
+        region: Region,
+        character: char,
+    ) {
+        if cursor.x + 1 >= region.dimensions.0 {
+            self.new_line_in(cursor, region);
+            if cursor.clipped {
+                return;
+            }
+        }
+        let (x, y) = (cursor.x, cursor.y);
+        match (region.to_absolute(x, y), region.to_absolute(x + 1, y)) {
+            (Some((x, y)), Some(_)) => {
+                let mut wide = cursor.character(character);
+                wide.width = 2;
+                let mut continuation = cursor.character(' ');
+                continuation.width = 0;
+                // The continuation is set first, so that replacing the continuation of the old character doesn't empty
+                // the new one
+                self.set_cell(x + 1, y, continuation);
+                self.set_cell(x, y, wide);
+            }
+            (Some((x, y)), None) | (None, Some((x, y))) => {
+                self.set_cell(x, y, cursor.character(' '))
+            }
+            (None, None) => {}
+        }
+        self.move_cursor_in(cursor, region, 2);
+    }
+
+    /// Attaches the combining character to the character before the cursor.
+    pub(crate) fn put_combining_char(&mut self, cursor: &TermCursor, region: Region, mark: char) {
+        let (mut x, mut y) = (cursor.x, cursor.y);
+        if cursor.clipped {
+            // The cursor is still on the last character written
+        } else if x > 0 {
+            x -= 1;
+        } else if y > 0 {
+            x = region.dimensions.0 - 1;
+            y -= 1;
+        } else {
+            return;
+        }
+        if x > 0 && self.get_cell_in(region, x, y).map(|c| c.width) == Some(0) {
+            x -= 1;
+        }
+        if let Some((x, y)) = region.to_absolute(x, y) {
+            self.chars[(y * self.width + x) as usize].add_combining_char(mark);
+        }
+    }
+}